@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+
+use crate::settings::Settings;
+
+pub struct FocusPlugin;
+
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Focus>()
+            .add_event::<MoveFocus>()
+            .add_event::<Activate>()
+            .add_systems(Startup, spawn_focus_indicator)
+            .add_systems(Update, (keyboard_focus, move_focus, update_focus_indicator).chain());
+    }
+}
+
+/// Marks UI elements that can be reached without a mouse.
+#[derive(Component, Default)]
+pub struct Focusable;
+
+#[derive(Resource, Default)]
+pub struct Focus(pub Option<Entity>);
+
+/// Moves focus to the closest visible `Focusable` in the given screen direction (y points down).
+#[derive(Event)]
+pub struct MoveFocus(pub Vec2);
+
+/// Sent when a focused element is activated without the mouse; handlers treat it like a click.
+#[derive(Event)]
+pub struct Activate(pub Entity);
+
+#[derive(Component)]
+struct FocusIndicator;
+
+fn spawn_focus_indicator(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Focus indicator"),
+        FocusIndicator,
+        Node {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        Outline { width: Val::Px(3.0), offset: Val::Px(2.0), ..default() },
+        BorderRadius::all(Val::Px(6.0)),
+        GlobalZIndex(i32::MAX - 2),
+        Visibility::Hidden,
+    ));
+}
+
+fn keyboard_focus(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut focus: ResMut<Focus>,
+    mut moves: EventWriter<MoveFocus>,
+    mut activations: EventWriter<Activate>,
+) {
+    // Reaching for the mouse hands control back to it.
+    if mouse.get_just_pressed().next().is_some() {
+        focus.0 = None;
+    }
+
+    for (key, direction) in [
+        (KeyCode::ArrowUp, Vec2::NEG_Y),
+        (KeyCode::ArrowDown, Vec2::Y),
+        (KeyCode::ArrowLeft, Vec2::NEG_X),
+        (KeyCode::ArrowRight, Vec2::X),
+    ] {
+        if keys.just_pressed(key) {
+            moves.write(MoveFocus(direction));
+        }
+    }
+
+    if keys.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space]) {
+        if let Some(entity) = focus.0 {
+            activations.write(Activate(entity));
+        }
+    }
+}
+
+fn move_focus(
+    mut moves: EventReader<MoveFocus>,
+    mut focus: ResMut<Focus>,
+    focusables: Query<(Entity, &GlobalTransform, &ComputedNode, &InheritedVisibility), With<Focusable>>,
+) {
+    for &MoveFocus(direction) in moves.read() {
+        let candidates = focusables.iter()
+            .filter(|(_, _, node, visibility)| visibility.get() && node.size() != Vec2::ZERO)
+            .map(|(entity, transform, _, _)| (entity, transform.translation().truncate()));
+
+        let current = focus.0.and_then(|entity| focusables.get(entity).ok());
+        focus.0 = match current {
+            Some((_, transform, _, _)) => {
+                let from = transform.translation().truncate();
+                // Prefer candidates straight ahead over ones that are closer but off to the side.
+                candidates
+                    .filter_map(|(entity, position)| {
+                        let delta = position - from;
+                        let along = delta.dot(direction);
+                        (along > 1.0).then_some((entity, along + 2.0 * delta.perp_dot(direction).abs()))
+                    })
+                    .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                    .map(|(entity, _)| entity)
+                    .or(focus.0)
+            }
+            None => candidates
+                .min_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+                .map(|(entity, _)| entity),
+        };
+    }
+}
+
+fn update_focus_indicator(
+    focus: Res<Focus>,
+    focusables: Query<(&GlobalTransform, &ComputedNode, &InheritedVisibility), With<Focusable>>,
+    indicator: Single<(&mut Node, &mut Outline, &mut Visibility), With<FocusIndicator>>,
+    settings: Res<Settings>,
+) {
+    let (mut node, mut outline, mut visibility) = indicator.into_inner();
+    let focused = focus.0
+        .and_then(|entity| focusables.get(entity).ok())
+        .filter(|(_, computed_node, inherited_visibility)| inherited_visibility.get() && computed_node.size() != Vec2::ZERO);
+
+    let Some((transform, computed_node, _)) = focused else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let scale = computed_node.inverse_scale_factor();
+    let rect = Rect::from_center_size(transform.translation().truncate(), computed_node.size());
+    node.left = Val::Px(rect.min.x * scale);
+    node.top = Val::Px(rect.min.y * scale);
+    node.width = Val::Px(rect.width() * scale);
+    node.height = Val::Px(rect.height() * scale);
+    outline.color = settings.theme.get().focus;
+    *visibility = Visibility::Inherited;
+}
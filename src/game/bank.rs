@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+
+use crate::{game::{grammar::{word, BLANK}, sentence::{text, Blank, Value, LINE_HEIGHT}}, settings::{Mode, Settings}, states::GameState};
+
+pub struct BankPlugin;
+
+impl Plugin for BankPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, render_bank.run_if(in_state(GameState::InGame)));
+    }
+}
+
+/// Ruled lines at the bottom of the text box that the bank takes up, leaving the rest of the page to sentences.
+pub const BANK_LINES: u32 = 2;
+
+/// Tokens of a `Mode::TokenBank` level that aren't in any sentence.
+#[derive(Component)]
+pub struct Bank;
+
+/// Sits at the bottom of the text box, so it folds away with the page between levels.
+pub fn token_bank(settings: &Settings) -> impl Bundle {
+    (
+        Name::new("Bank"),
+        Bank,
+        Node {
+            display: if settings.mode == Mode::TokenBank { Display::Flex } else { Display::None },
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            bottom: Val::Px(0.0),
+            height: Val::Px(BANK_LINES as f32 * LINE_HEIGHT),
+            flex_wrap: FlexWrap::Wrap,
+            align_content: AlignContent::FlexStart,
+            column_gap: Val::Px(8.0),
+            ..default()
+        },
+        BorderRadius::all(Val::Px(8.0)),
+        BackgroundColor(settings.theme.get().ink.with_alpha(0.05)),
+    )
+}
+
+/// Replaces the bank's tokens with the next level's.
+pub fn fill_bank(
+    tokens: In<Vec<Value>>,
+    mut commands: Commands,
+    bank: Single<Entity, With<Bank>>,
+    settings: Res<Settings>,
+) {
+    let theme = settings.theme.get();
+    commands.entity(*bank).despawn_related::<Children>().with_children(|commands| {
+        for &value in tokens.iter() {
+            commands.spawn(text(value, theme));
+        }
+    });
+}
+
+/// Bank tokens aren't part of a sentence, so an Id always reads as an ordinal, never as "this".
+fn render_bank(
+    bank: Single<&Children, With<Bank>>,
+    mut tokens: Query<(Ref<Value>, Has<Blank>, &mut Text)>,
+    settings: Res<Settings>,
+) {
+    let mut tokens = tokens.iter_many_mut(*bank);
+    while let Some((value, blank, mut text)) = tokens.fetch_next() {
+        if !settings.is_changed() && !value.is_changed() {
+            continue;
+        }
+        text.0 = if blank {
+            BLANK.to_string()
+        } else {
+            word(*value, 0, None, settings.language, settings.spelled_numbers)
+        };
+    }
+}
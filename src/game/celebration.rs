@@ -0,0 +1,130 @@
+use std::f32::consts::TAU;
+
+use bevy::{prelude::*, ui::FocusPolicy};
+use rand::random;
+
+use crate::{game::{levels::CurrentLevel, page::{CurrentPage, Pages}, sentence::SentenceId, Notebook, Pointer}, settings::Settings, tween::{Animation, Property, Tween}};
+
+pub struct CelebrationPlugin;
+
+impl Plugin for CelebrationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, finish_celebration.run_if(any_with_component::<Celebration>));
+    }
+}
+
+const LIGHT_UP_INTERVAL: f32 = 0.12;
+const LIGHT_UP_DURATION: f32 = 0.25;
+const STAMP_DURATION: f32 = 0.35;
+const STAMP_SIZE: f32 = 200.0;
+/// Middle of the right-hand page, in notebook pixels.
+const STAMP_CENTER: Vec2 = Vec2::new(732.0, 483.0);
+const CONFETTI_COUNT: usize = 60;
+const CONFETTI_DURATION: f32 = 1.6;
+const CONFETTI_SIZE: Vec2 = Vec2::new(8.0, 14.0);
+/// How long the celebration lingers once the confetti is out, unless it is skipped.
+const CELEBRATION_HOLD: f32 = 1.0;
+
+/// Covers the notebook while a solved level is celebrated. Clicking anywhere skips to the next level.
+#[derive(Component)]
+pub struct Celebration(Timer);
+
+/// Lights up the sentences on the page one by one, stamps the notebook and throws confetti,
+/// then moves on to the next level.
+pub fn celebrate(
+    mut commands: Commands,
+    notebook: Single<Entity, With<Notebook>>,
+    sentences: Query<(Entity, &SentenceId)>,
+    pages: Res<Pages>,
+    page: Res<CurrentPage>,
+    settings: Res<Settings>,
+    asset_server: Res<AssetServer>,
+) {
+    let theme = settings.theme.get();
+    let mut sentences = sentences.iter()
+        .filter(|&(_, &SentenceId(id))| pages.page_of(id) == page.0)
+        .collect::<Vec<_>>();
+    sentences.sort_by_key(|&(_, &SentenceId(id))| id);
+
+    for (i, &(entity, _)) in sentences.iter().enumerate() {
+        let light_up = Tween::new(Property::BackgroundColor(Color::NONE, theme.consistent.with_alpha(0.35)), LIGHT_UP_DURATION);
+        commands.entity(entity).insert(Animation::new(light_up).after(i as f32 * LIGHT_UP_INTERVAL));
+    }
+
+    let stamp_delay = sentences.len() as f32 * LIGHT_UP_INTERVAL;
+    let stamp = Animation::new(Tween::new(Property::Scale(Vec2::splat(3.0), Vec2::ONE), STAMP_DURATION).ease(EaseFunction::BackOut))
+        .with(Tween::new(Property::Opacity(0.0, 1.0), STAMP_DURATION / 2.0))
+        .with(Tween::new(Property::Rotation(-0.6, -0.2), STAMP_DURATION).ease(EaseFunction::QuadraticOut))
+        .after(stamp_delay);
+
+    // The confetti bursts out from under the stamp as it lands, so it stays shrunk to nothing until then.
+    let burst_delay = stamp_delay + STAMP_DURATION;
+    let colors = [theme.id_token, theme.bool_token, theme.number_token, theme.consistent, theme.highlight_primary];
+    let start = STAMP_CENTER - CONFETTI_SIZE / 2.0;
+    let confetti = (0..CONFETTI_COUNT).map(|_| {
+        let direction = Vec2::from_angle(random::<f32>() * TAU);
+        let end = start + direction * (150.0 + random::<f32>() * 300.0) + Vec2::new(0.0, 150.0);
+        let duration = CONFETTI_DURATION * (0.7 + random::<f32>() * 0.3);
+        let animation = Animation::new(Tween::new(Property::Position(start, end), duration).ease(EaseFunction::CubicOut))
+            .with(Tween::new(Property::Scale(Vec2::ZERO, Vec2::ONE), 0.15))
+            .with(Tween::new(Property::Rotation(0.0, (random::<f32>() - 0.5) * 4.0 * TAU), duration))
+            .with(Tween::new(Property::Opacity(1.0, 0.0), duration).ease(EaseFunction::CubicIn))
+            .after(burst_delay);
+        (
+            Name::new("Confetti"),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Px(CONFETTI_SIZE.x),
+                height: Val::Px(CONFETTI_SIZE.y),
+                ..default()
+            },
+            BackgroundColor(colors[random::<u32>() as usize % colors.len()]),
+            animation,
+        )
+    }).collect::<Vec<_>>();
+
+    let celebration = commands.spawn((
+        Name::new("Celebration"),
+        Celebration(Timer::from_seconds(burst_delay + CONFETTI_DURATION + CELEBRATION_HOLD, TimerMode::Once)),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        FocusPolicy::Block,
+    )).with_children(|commands| {
+        commands.spawn((
+            Name::new("Stamp"),
+            ImageNode::new(asset_server.load(theme.checkbox_true)),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(STAMP_CENTER.x - STAMP_SIZE / 2.0),
+                top: Val::Px(STAMP_CENTER.y - STAMP_SIZE / 2.0),
+                width: Val::Px(STAMP_SIZE),
+                height: Val::Px(STAMP_SIZE),
+                ..default()
+            },
+            stamp,
+        ));
+        for piece in confetti {
+            commands.spawn(piece);
+        }
+    }).id();
+    commands.entity(*notebook).add_child(celebration);
+}
+
+fn finish_celebration(
+    mut commands: Commands,
+    celebration: Single<(Entity, &mut Celebration)>,
+    pointer: Pointer,
+    mut current_level: ResMut<CurrentLevel>,
+    time: Res<Time>,
+) {
+    let (entity, mut celebration) = celebration.into_inner();
+    if celebration.0.tick(time.delta()).finished() || pointer.just_pressed() {
+        commands.entity(entity).despawn();
+        current_level.0 += 1;
+    }
+}
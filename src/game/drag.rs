@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+
+use crate::{game::{sentence::{click_token, swap, token_ghost, Locked, Selected, Value, GHOST_OPACITY}, Game, Pointer}, settings::Settings, tween::{Animation, Property, Tween}};
+
+pub struct DragPlugin;
+
+impl Plugin for DragPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Drag>()
+            .add_systems(Update, (press_token, drag_token, drop_token).chain());
+    }
+}
+
+/// How far the pointer has to travel, in logical pixels, before a press turns into a drag.
+const DRAG_THRESHOLD: f32 = 8.0;
+const SNAP_BACK_DURATION: f32 = 0.25;
+
+/// The token the player is pressing, and the ghost following the pointer once it is being dragged.
+#[derive(Resource, Default)]
+struct Drag {
+    token: Option<Entity>,
+    ghost: Option<Entity>,
+    /// Pointer positions are kept in physical pixels, the same space `ComputedNode` and `GlobalTransform` use.
+    start: Vec2,
+    position: Vec2,
+    grab_offset: Vec2,
+}
+
+#[derive(Component)]
+struct DragGhost;
+
+pub fn node_rect(node: &ComputedNode, transform: &GlobalTransform) -> Rect {
+    Rect::from_center_size(transform.translation().truncate(), node.size())
+}
+
+fn press_token(
+    interaction_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<Value>, Without<Locked>)>,
+    mut drag: ResMut<Drag>,
+    pointer: Pointer,
+) {
+    for (entity, interaction) in interaction_query {
+        if *interaction == Interaction::Pressed {
+            let position = pointer.physical_position().unwrap_or_default();
+            drag.token = Some(entity);
+            drag.start = position;
+            drag.position = position;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn drag_token(
+    mut commands: Commands,
+    mut drag: ResMut<Drag>,
+    mut selected: ResMut<Selected>,
+    pointer: Pointer,
+    game: Single<Entity, With<Game>>,
+    mut tokens: Query<(Entity, &Value, &Text, &ComputedNode, &GlobalTransform, &mut Outline), Without<Locked>>,
+    mut ghosts: Query<&mut Node, With<DragGhost>>,
+    settings: Res<Settings>,
+) {
+    let theme = settings.theme.get();
+    let Some(token) = drag.token else { return };
+    let Some(position) = pointer.physical_position() else { return };
+    drag.position = position;
+
+    if drag.ghost.is_none() {
+        if position.distance(drag.start) < DRAG_THRESHOLD * pointer.scale_factor() {
+            return;
+        }
+        let Ok((_, &value, text, node, transform, _)) = tokens.get(token) else {
+            drag.token = None;
+            return;
+        };
+        drag.grab_offset = drag.start - node_rect(node, transform).min;
+        let ghost = commands.spawn((DragGhost, token_ghost(text.0.clone(), value, theme))).id();
+
+        // Dragging replaces any click selection with highlighted drop targets.
+        selected.0 = None;
+        for (entity, other, _, _, _, mut outline) in tokens.iter_mut() {
+            if entity != token && other.same_kind(&value) {
+                outline.width = Val::Px(2.0);
+                outline.color = theme.consistent;
+            } else {
+                outline.width = Val::Px(0.0);
+            }
+        }
+
+        commands.entity(*game).add_child(ghost);
+        drag.ghost = Some(ghost);
+    }
+
+    if let Some(mut node) = drag.ghost.and_then(|ghost| ghosts.get_mut(ghost).ok()) {
+        let top_left = pointer.to_ui(position - drag.grab_offset);
+        node.left = Val::Px(top_left.x);
+        node.top = Val::Px(top_left.y);
+    }
+}
+
+fn drop_token(
+    mut commands: Commands,
+    mut drag: ResMut<Drag>,
+    pointer: Pointer,
+    mut tokens: Query<(Entity, &Value, &ComputedNode, &GlobalTransform, &mut Outline), Without<Locked>>,
+    ghosts: Query<&Node, With<DragGhost>>,
+    settings: Res<Settings>,
+) {
+    if !pointer.just_released() {
+        return;
+    }
+    let Some(token) = drag.token.take() else { return };
+    let Some(ghost) = drag.ghost.take() else {
+        commands.run_system_cached_with(click_token, token);
+        return;
+    };
+
+    for (_, _, _, _, mut outline) in tokens.iter_mut() {
+        outline.width = Val::Px(0.0);
+        outline.color = settings.theme.get().ink;
+    }
+
+    let Ok((_, &value, node, transform, _)) = tokens.get(token) else {
+        commands.entity(ghost).despawn();
+        return;
+    };
+    let target = tokens.iter()
+        .find(|&(entity, _, node, transform, _)| entity != token && node_rect(node, transform).contains(drag.position));
+
+    if let Some((target, _, _, _, _)) = target {
+        // Mismatched tokens are rejected by `swap` itself.
+        commands.run_system_cached_with(swap, (token, target));
+    }
+    match target {
+        Some((_, other, _, _, _)) if other.same_kind(&value) => {
+            commands.entity(ghost).despawn();
+        }
+        _ => {
+            let from = ghosts.get(ghost).map(|node| match (node.left, node.top) {
+                (Val::Px(left), Val::Px(top)) => Vec2::new(left, top),
+                _ => Vec2::ZERO,
+            }).unwrap_or_default();
+            // The ghost fades away as it flies back to the token it was dragged from.
+            let to = pointer.to_ui(node_rect(node, transform).min);
+            let snap_back = Animation::new(Tween::new(Property::Position(from, to), SNAP_BACK_DURATION).ease(EaseFunction::CubicOut))
+                .with(Tween::new(Property::Opacity(GHOST_OPACITY, 0.0), SNAP_BACK_DURATION).ease(EaseFunction::QuadraticIn));
+            commands.entity(ghost).insert(snap_back.despawn_when_finished());
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use std::collections::BTreeSet;
+
+use crate::{game::sentence::{Sentence, Value}, locale::Language};
+
+/// The quantity a sentence looked at on the board when it was evaluated.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Observation {
+    /// The referenced sentence is marked with this truth value.
+    Marked(bool),
+    /// This many sentences are marked with the claimed truth value.
+    Count(u32),
+    /// Distance to the closest other sentence marked with the claimed truth value, if there is one.
+    /// A claim of distance 0 is about the sentence's own checkbox instead.
+    Distance(Option<u32>),
+    /// Number of maximal runs of equal consecutive truth values.
+    Groups(u32),
+    /// Truth values of both referenced sentences.
+    Pair(bool, bool),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Evaluation {
+    pub truth: bool,
+    pub observation: Observation,
+}
+
+impl Sentence {
+    /// Evaluates the sentence at position `sentence_id` against the marked `truths`.
+    pub fn evaluate(&self, values: &[Value], sentence_id: u32, truths: &[bool]) -> Evaluation {
+        let marked = |id: u32| truths[(id - 1) as usize];
+        match *self {
+            Sentence::IdSentenceIsBool => {
+                let Value::Id(id) = values[0] else { panic!("Expected Value::Id") };
+                let Value::Bool(truth) = values[1] else { panic!("Expected Value::Bool") };
+
+                Evaluation {
+                    truth: marked(id) == truth,
+                    observation: Observation::Marked(marked(id)),
+                }
+            }
+            Sentence::ThereAreNumberOfBoolSentences => {
+                let Value::Number(number) = values[0] else { panic!("Expected Value::Number") };
+                let Value::Bool(truth) = values[1] else { panic!("Expected Value::Bool") };
+
+                let count = truths.iter().filter(|&&t| t == truth).count() as u32;
+                Evaluation {
+                    truth: count == number,
+                    observation: Observation::Count(count),
+                }
+            }
+            Sentence::ClosestBoolIsNumberAway => {
+                let Value::Bool(truth) = values[0] else { panic!("Expected Value::Bool") };
+                let Value::Number(number) = values[1] else { panic!("Expected Value::Number") };
+
+                let distance = (1..truths.len() as u32).find(|&d| {
+                    (sentence_id > d && marked(sentence_id - d) == truth)
+                        || (sentence_id + d <= truths.len() as u32 && marked(sentence_id + d) == truth)
+                });
+                Evaluation {
+                    truth: if number == 0 { marked(sentence_id) == truth } else { distance == Some(number) },
+                    observation: Observation::Distance(distance),
+                }
+            }
+            Sentence::ThereAreNumberOfAlternatingGroups => {
+                let Value::Number(number) = values[0] else { panic!("Expected Value::Number") };
+
+                let groups = alternating_groups(truths);
+                Evaluation {
+                    truth: groups == number,
+                    observation: Observation::Groups(groups),
+                }
+            }
+            Sentence::IdSentenceAndIdSentenceAreTheSame | Sentence::IdSentenceAndIdSentenceAreDifferent => {
+                let Value::Id(id1) = values[0] else { panic!("Expected Value::Id") };
+                let Value::Id(id2) = values[1] else { panic!("Expected Value::Id") };
+
+                let same = marked(id1) == marked(id2);
+                Evaluation {
+                    truth: same == (*self == Sentence::IdSentenceAndIdSentenceAreTheSame),
+                    observation: Observation::Pair(marked(id1), marked(id2)),
+                }
+            }
+        }
+    }
+
+    /// Evaluates the sentence while some checkboxes are still unknown.
+    ///
+    /// Returns the truth value only if it is the same for every way of filling in the unknown checkboxes.
+    pub fn evaluate_partial(&self, values: &[Value], sentence_id: u32, truths: &[Option<bool>]) -> Option<bool> {
+        if let Some(truths) = truths.iter().copied().collect::<Option<Vec<_>>>() {
+            return Some(self.evaluate(values, sentence_id, &truths).truth);
+        }
+
+        let marked = |id: u32| truths[(id - 1) as usize];
+        match *self {
+            Sentence::IdSentenceIsBool => {
+                let Value::Id(id) = values[0] else { panic!("Expected Value::Id") };
+                let Value::Bool(truth) = values[1] else { panic!("Expected Value::Bool") };
+
+                marked(id).map(|marked| marked == truth)
+            }
+            Sentence::ThereAreNumberOfBoolSentences => {
+                let Value::Number(number) = values[0] else { panic!("Expected Value::Number") };
+                let Value::Bool(truth) = values[1] else { panic!("Expected Value::Bool") };
+
+                let known = truths.iter().filter(|&&t| t == Some(truth)).count() as u32;
+                let unknown = truths.iter().filter(|t| t.is_none()).count() as u32;
+                if number < known || number > known + unknown {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            Sentence::ClosestBoolIsNumberAway => {
+                let Value::Bool(truth) = values[0] else { panic!("Expected Value::Bool") };
+                let Value::Number(number) = values[1] else { panic!("Expected Value::Number") };
+
+                // Every checkbox appears at most once in the formula, so three-valued logic gives an exact answer.
+                // At distance 0 the sentence only finds its own checkbox.
+                let at_distance = |d: u32| {
+                    let before = (sentence_id > d).then(|| marked(sentence_id - d));
+                    let after = (d != 0 && sentence_id + d <= truths.len() as u32).then(|| marked(sentence_id + d));
+                    before.into_iter().chain(after)
+                };
+                let none_closer = kleene_all((1..number).flat_map(at_distance).map(|t| t.map(|t| t != truth)));
+                let one_at_distance = kleene_any(at_distance(number).map(|t| t.map(|t| t == truth)));
+                kleene_all([none_closer, one_at_distance])
+            }
+            Sentence::ThereAreNumberOfAlternatingGroups => {
+                let Value::Number(number) = values[0] else { panic!("Expected Value::Number") };
+
+                let groups = possible_alternating_groups(truths);
+                if !groups.contains(&number) {
+                    Some(false)
+                } else if groups.len() == 1 {
+                    Some(true)
+                } else {
+                    None
+                }
+            }
+            Sentence::IdSentenceAndIdSentenceAreTheSame | Sentence::IdSentenceAndIdSentenceAreDifferent => {
+                let Value::Id(id1) = values[0] else { panic!("Expected Value::Id") };
+                let Value::Id(id2) = values[1] else { panic!("Expected Value::Id") };
+
+                let claims_same = *self == Sentence::IdSentenceAndIdSentenceAreTheSame;
+                if id1 == id2 {
+                    return Some(claims_same);
+                }
+                match (marked(id1), marked(id2)) {
+                    (Some(first), Some(second)) => Some((first == second) == claims_same),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// States exactly how the sentence is evaluated, whatever the board looks like.
+    pub fn rule(&self, language: Language) -> &'static str {
+        language.get(match self {
+            Sentence::IdSentenceIsBool => "rule.is_bool",
+            Sentence::ThereAreNumberOfBoolSentences => "rule.count",
+            Sentence::ClosestBoolIsNumberAway => "rule.closest",
+            Sentence::ThereAreNumberOfAlternatingGroups => "rule.groups",
+            Sentence::IdSentenceAndIdSentenceAreTheSame => "rule.same",
+            Sentence::IdSentenceAndIdSentenceAreDifferent => "rule.different",
+        })
+    }
+
+    /// Explains to the player why the sentence at `sentence_id` evaluated the way it did.
+    pub fn explain(&self, values: &[Value], sentence_id: u32, evaluation: &Evaluation, language: Language) -> String {
+        let reference = |id: u32| if id == sentence_id {
+            language.get("reference.this").to_string()
+        } else {
+            language.format(language.get("reference.other"), &[&language.ordinal(id)])
+        };
+        let format = |key: &str, arguments: &[&str]| language.format(language.get(key), arguments);
+        let format_plural = |key: &str, n: u32, arguments: &[&str]| language.format(language.get_plural(key, n), arguments);
+
+        let reason = match (*self, evaluation.observation) {
+            (Sentence::IdSentenceIsBool, Observation::Marked(marked)) => {
+                let Value::Id(id) = values[0] else { panic!("Expected Value::Id") };
+                let Value::Bool(truth) = values[1] else { panic!("Expected Value::Bool") };
+                if evaluation.truth {
+                    format("explain.marked.consistent", &[&reference(id), &marked.to_string()])
+                } else {
+                    format("explain.marked.contradicted", &[&reference(id), &truth.to_string(), &marked.to_string()])
+                }
+            }
+            (Sentence::ThereAreNumberOfBoolSentences, Observation::Count(count)) => {
+                let Value::Number(number) = values[0] else { panic!("Expected Value::Number") };
+                let Value::Bool(truth) = values[1] else { panic!("Expected Value::Bool") };
+                if evaluation.truth {
+                    format_plural("explain.count.consistent", count, &[&count.to_string(), &truth.to_string()])
+                } else {
+                    format_plural("explain.count.contradicted", number, &[&number.to_string(), &truth.to_string(), &count.to_string()])
+                }
+            }
+            (Sentence::ClosestBoolIsNumberAway, Observation::Distance(distance)) => {
+                let Value::Bool(truth) = values[0] else { panic!("Expected Value::Bool") };
+                let Value::Number(number) = values[1] else { panic!("Expected Value::Number") };
+                match distance {
+                    _ if number == 0 && evaluation.truth =>
+                        format("explain.marked.consistent", &[&reference(sentence_id), &truth.to_string()]),
+                    _ if number == 0 =>
+                        format("explain.marked.contradicted", &[&reference(sentence_id), &truth.to_string(), &(!truth).to_string()]),
+                    Some(distance) if evaluation.truth =>
+                        format_plural("explain.closest.consistent", distance, &[&truth.to_string(), &distance.to_string()]),
+                    Some(distance) =>
+                        format_plural("explain.closest.actual", distance, &[&truth.to_string(), &distance.to_string()]),
+                    None =>
+                        format("explain.closest.none", &[&truth.to_string()]),
+                }
+            }
+            (Sentence::ThereAreNumberOfAlternatingGroups, Observation::Groups(groups)) => {
+                let Value::Number(number) = values[0] else { panic!("Expected Value::Number") };
+                if evaluation.truth {
+                    format_plural("explain.groups.consistent", groups, &[&groups.to_string()])
+                } else {
+                    format_plural("explain.groups.contradicted", number, &[&number.to_string(), &groups.to_string()])
+                }
+            }
+            (Sentence::IdSentenceAndIdSentenceAreTheSame | Sentence::IdSentenceAndIdSentenceAreDifferent, Observation::Pair(first, second)) => {
+                let Value::Id(id1) = values[0] else { panic!("Expected Value::Id") };
+                let Value::Id(id2) = values[1] else { panic!("Expected Value::Id") };
+                let key = if evaluation.truth { "explain.pair.consistent" } else { "explain.pair.contradicted" };
+                format(key, &[&reference(id1), &first.to_string(), &reference(id2), &second.to_string()])
+            }
+            (sentence, observation) => panic!("{sentence:?} cannot observe {observation:?}"),
+        };
+
+        format("explain.conclusion", &[&reason, &evaluation.truth.to_string()])
+    }
+}
+
+/// Counts maximal runs of equal consecutive truth values.
+pub fn alternating_groups(truths: &[bool]) -> u32 {
+    if truths.is_empty() {
+        return 0;
+    }
+    1 + truths.windows(2).filter(|pair| pair[0] != pair[1]).count() as u32
+}
+
+/// All group counts reachable by filling in the unknown truth values.
+fn possible_alternating_groups(truths: &[Option<bool>]) -> BTreeSet<u32> {
+    let options = |truth: Option<bool>| match truth {
+        Some(truth) => vec![truth],
+        None => vec![false, true],
+    };
+
+    let mut states = BTreeSet::<(bool, u32)>::new();
+    for (i, &truth) in truths.iter().enumerate() {
+        states = if i == 0 {
+            options(truth).into_iter().map(|t| (t, 1)).collect()
+        } else {
+            states.iter()
+                .flat_map(|&(last, groups)| options(truth).into_iter().map(move |t| (t, groups + (t != last) as u32)))
+                .collect()
+        };
+    }
+    states.into_iter().map(|(_, groups)| groups).collect()
+}
+
+fn kleene_all(truths: impl IntoIterator<Item = Option<bool>>) -> Option<bool> {
+    let mut result = Some(true);
+    for truth in truths {
+        match truth {
+            Some(false) => return Some(false),
+            None => result = None,
+            Some(true) => {}
+        }
+    }
+    result
+}
+
+fn kleene_any(truths: impl IntoIterator<Item = Option<bool>>) -> Option<bool> {
+    kleene_all(truths.into_iter().map(|truth| truth.map(|t| !t))).map(|t| !t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENTENCES: [Sentence; 6] = [
+        Sentence::IdSentenceIsBool,
+        Sentence::ThereAreNumberOfBoolSentences,
+        Sentence::ClosestBoolIsNumberAway,
+        Sentence::ThereAreNumberOfAlternatingGroups,
+        Sentence::IdSentenceAndIdSentenceAreTheSame,
+        Sentence::IdSentenceAndIdSentenceAreDifferent,
+    ];
+
+    /// Every way to fill in the sentence's slots on a level of `n` sentences, including numbers out of range.
+    fn all_values(sentence: Sentence, n: u32) -> Vec<Vec<Value>> {
+        let ids = (1..=n).map(Value::Id).collect::<Vec<_>>();
+        let numbers = (0..=n + 1).map(Value::Number).collect::<Vec<_>>();
+        let bools = [false, true].map(Value::Bool).to_vec();
+        let slots = match sentence {
+            Sentence::IdSentenceIsBool => vec![ids, bools],
+            Sentence::ThereAreNumberOfBoolSentences => vec![numbers, bools],
+            Sentence::ClosestBoolIsNumberAway => vec![bools, numbers],
+            Sentence::ThereAreNumberOfAlternatingGroups => vec![numbers],
+            Sentence::IdSentenceAndIdSentenceAreTheSame | Sentence::IdSentenceAndIdSentenceAreDifferent => vec![ids.clone(), ids],
+        };
+        slots.into_iter().fold(vec![Vec::new()], |combinations, slot| {
+            combinations.iter()
+                .flat_map(|values| slot.iter().map(move |&value| [values.clone(), vec![value]].concat()))
+                .collect()
+        })
+    }
+
+    /// Every marking of `n` checkboxes where each one is false, true or unknown.
+    fn partial_markings(n: usize) -> Vec<Vec<Option<bool>>> {
+        (0..3usize.pow(n as u32))
+            .map(|code| (0..n).map(|i| [None, Some(false), Some(true)][code / 3usize.pow(i as u32) % 3]).collect())
+            .collect()
+    }
+
+    fn completions(truths: &[Option<bool>]) -> Vec<Vec<bool>> {
+        truths.iter().fold(vec![Vec::new()], |completions, truth| {
+            let options = truth.map_or(vec![false, true], |truth| vec![truth]);
+            completions.iter()
+                .flat_map(|completion| options.iter().map(move |&t| [completion.clone(), vec![t]].concat()))
+                .collect()
+        })
+    }
+
+    #[test]
+    fn partial_evaluation_is_exact() {
+        for n in 1..=4 {
+            for sentence in SENTENCES {
+                for values in all_values(sentence, n) {
+                    for sentence_id in 1..=n {
+                        for truths in partial_markings(n as usize) {
+                            let outcomes = completions(&truths).iter()
+                                .map(|completion| sentence.evaluate(&values, sentence_id, completion).truth)
+                                .collect::<BTreeSet<_>>();
+                            let expected = (outcomes.len() == 1).then(|| outcomes.into_iter().next().unwrap());
+                            assert_eq!(
+                                sentence.evaluate_partial(&values, sentence_id, &truths), expected,
+                                "{sentence:?} {values:?} as sentence {sentence_id} marked {truths:?}",
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn closest_looks_both_ways_and_finds_itself_only_at_zero() {
+        let closest = |truth, number, sentence_id, truths: &[bool]| {
+            Sentence::ClosestBoolIsNumberAway.evaluate(&[Value::Bool(truth), Value::Number(number)], sentence_id, truths).truth
+        };
+        assert!(closest(true, 1, 2, &[true, false, false]));
+        assert!(closest(true, 2, 1, &[true, false, true]));
+        assert!(closest(true, 0, 1, &[true, false]));
+        assert!(!closest(false, 0, 1, &[true, false]));
+        assert!(!closest(true, 1, 1, &[true, false]));
+    }
+
+    #[test]
+    fn groups_are_maximal_runs() {
+        assert_eq!(alternating_groups(&[]), 0);
+        assert_eq!(alternating_groups(&[true, true, false, true]), 3);
+        assert_eq!(alternating_groups(&[false, false, false]), 1);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{game::sentence::{Sentence, Value}, locale::{parse_template, Language, TemplatePart}};
+
+/// A piece of a rendered sentence: fixed words, or the text of the token in the given slot.
+pub enum Segment {
+    Plain(String),
+    Token(usize, String),
+}
+
+impl Sentence {
+    fn template_key(&self) -> &'static str {
+        match self {
+            Sentence::IdSentenceIsBool => "sentence.is_bool",
+            Sentence::ThereAreNumberOfBoolSentences => "sentence.count",
+            Sentence::ClosestBoolIsNumberAway => "sentence.closest",
+            Sentence::ThereAreNumberOfAlternatingGroups => "sentence.groups",
+            Sentence::IdSentenceAndIdSentenceAreTheSame => "sentence.same",
+            Sentence::IdSentenceAndIdSentenceAreDifferent => "sentence.different",
+        }
+    }
+
+    /// The slot holding the number the rest of the sentence has to agree with.
+    fn counted_slot(&self) -> Option<usize> {
+        match self {
+            Sentence::ThereAreNumberOfBoolSentences | Sentence::ThereAreNumberOfAlternatingGroups => Some(0),
+            Sentence::ClosestBoolIsNumberAway => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Words of the sentence at position `sentence_id`, agreeing with the values currently in its slots.
+    /// Templates can place the slots in any order, so the tokens are laid out in the order the segments come in.
+    /// Blank slots (`None`) read as `BLANK`, and a blank count takes the plural form used for zero.
+    pub fn render(&self, values: &[Option<Value>], sentence_id: u32, language: Language, spelled_numbers: bool) -> Vec<Segment> {
+        let template = match self.counted_slot().map(|slot| values[slot]) {
+            Some(Some(Value::Number(n))) => language.get_plural(self.template_key(), n),
+            Some(None) => language.get_plural(self.template_key(), 0),
+            Some(Some(_)) => panic!("Expected Value::Number"),
+            None => language.get(self.template_key()),
+        };
+
+        let mut segments = parse_template(template)
+            .into_iter()
+            .map(|part| match part {
+                TemplatePart::Text(text) => Segment::Plain(text.to_string()),
+                TemplatePart::Slot(slot, form) => Segment::Token(slot, match values[slot] {
+                    Some(value) => word(value, sentence_id, form, language, spelled_numbers),
+                    None => BLANK.to_string(),
+                }),
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(Segment::Plain(text) | Segment::Token(_, text)) = segments.first_mut() {
+            *text = capitalize(text);
+        }
+        let prefix = language.format(language.get("sentence.prefix"), &[&sentence_id.to_string()]);
+        segments.insert(0, Segment::Plain(prefix));
+        segments
+    }
+}
+
+/// Text of a slot that no token has been placed in yet.
+pub const BLANK: &str = "____";
+
+/// How a token reads inside its sentence. "this" is capitalized by `render` when it starts the sentence.
+pub fn word(value: Value, sentence_id: u32, form: Option<&str>, language: Language, spelled_numbers: bool) -> String {
+    match value {
+        Value::Id(id) if id == sentence_id => language.word("this", form),
+        Value::Id(id) => language.ordinal(id),
+        Value::Bool(b) => language.word(&b.to_string(), form),
+        Value::Number(n) if spelled_numbers => language.spelled(n, form),
+        Value::Number(n) => format!("{n}"),
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(segments: Vec<Segment>) -> String {
+        segments.into_iter().map(|(Segment::Plain(text) | Segment::Token(_, text))| text).collect()
+    }
+
+    fn count(n: u32, language: Language, spelled_numbers: bool) -> String {
+        text(Sentence::ThereAreNumberOfBoolSentences.render(&[Some(Value::Number(n)), Some(Value::Bool(true))], 1, language, spelled_numbers))
+    }
+
+    #[test]
+    fn agrees_with_counts() {
+        assert_eq!(count(1, Language::English, false), "1. There is 1 sentence that is true");
+        assert_eq!(count(2, Language::English, false), "1. There are 2 sentences that are true");
+        assert_eq!(count(0, Language::English, true), "1. There are zero sentences that are true");
+    }
+
+    #[test]
+    fn takes_polish_forms() {
+        assert_eq!(count(1, Language::Polish, true), "1) Prawdziwe jest dokładnie jedno zdanie");
+        assert_eq!(count(22, Language::Polish, false), "1) Prawdziwe są dokładnie 22 zdania");
+        assert_eq!(count(12, Language::Polish, false), "1) Prawdziwych jest dokładnie 12 zdań");
+    }
+
+    #[test]
+    fn capitalizes_this_at_the_start() {
+        let is_false = |sentence_id: u32, language: Language| {
+            text(Sentence::IdSentenceIsBool.render(&[Some(Value::Id(1)), Some(Value::Bool(false))], sentence_id, language, false))
+        };
+        assert_eq!(is_false(1, Language::English), "1. This sentence is false");
+        assert_eq!(is_false(2, Language::English), "2. 1st sentence is false");
+        assert_eq!(is_false(1, Language::Polish), "1) To zdanie jest fałszywe");
+    }
+
+    #[test]
+    fn blank_slots() {
+        let closest = Sentence::ClosestBoolIsNumberAway.render(&[Some(Value::Bool(true)), None], 2, Language::English, false);
+        assert_eq!(text(closest), "2. The closest true sentence is ____ spots away");
+    }
+}
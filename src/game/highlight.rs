@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+
+use crate::{game::{sentence::{Blank, Board, Sentence, SentenceId, Value}, Checkbox}, settings::Settings, states::GameState, theme::Theme};
+
+pub struct HighlightPlugin;
+
+impl Plugin for HighlightPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, highlight_references.run_if(in_state(GameState::InGame)));
+    }
+}
+
+const HIGHLIGHT_WIDTH: Val = Val::Px(2.0);
+
+/// Sentences the hovered sentence looks at, by position, together with the color to outline them with.
+fn references(sentence: Sentence, values: &[Value], sentence_id: u32, truths: &[Option<bool>], theme: &Theme) -> Vec<(u32, Color)> {
+    let (primary, secondary) = (theme.highlight_primary, theme.highlight_secondary);
+    let positions = 1..=truths.len() as u32;
+    let marked = |id: u32| truths[(id - 1) as usize];
+    match sentence {
+        Sentence::IdSentenceIsBool => {
+            let Value::Id(id) = values[0] else { panic!("Expected Value::Id") };
+            vec![(id, primary)]
+        }
+        Sentence::ThereAreNumberOfBoolSentences => {
+            let Value::Bool(truth) = values[1] else { panic!("Expected Value::Bool") };
+            positions.filter(|&id| marked(id) == Some(truth)).map(|id| (id, primary)).collect()
+        }
+        Sentence::ClosestBoolIsNumberAway => {
+            let Value::Number(number) = values[1] else { panic!("Expected Value::Number") };
+            positions
+                .filter(|&id| id != sentence_id && id.abs_diff(sentence_id) <= number)
+                .map(|id| (id, if id.abs_diff(sentence_id) == number { primary } else { secondary }))
+                .collect()
+        }
+        Sentence::ThereAreNumberOfAlternatingGroups => {
+            // Unknown checkboxes don't belong to any group, so they split the highlighted runs.
+            let mut group = 0;
+            positions
+                .filter_map(|id| {
+                    if id > 1 && marked(id) != marked(id - 1) {
+                        group += 1;
+                    }
+                    marked(id).map(|_| (id, if group % 2 == 0 { primary } else { secondary }))
+                })
+                .collect()
+        }
+        Sentence::IdSentenceAndIdSentenceAreTheSame | Sentence::IdSentenceAndIdSentenceAreDifferent => {
+            let Value::Id(id1) = values[0] else { panic!("Expected Value::Id") };
+            let Value::Id(id2) = values[1] else { panic!("Expected Value::Id") };
+            vec![(id1, primary), (id2, primary)]
+        }
+    }
+}
+
+fn highlight_references(
+    board: Board,
+    tokens: Query<(&Interaction, &Value, Has<Blank>, &ChildOf)>,
+    sentences: Query<(Entity, &Interaction, &Sentence, &SentenceId)>,
+    mut outlines: Query<&mut Outline, Or<(With<Sentence>, With<Checkbox>)>>,
+    settings: Res<Settings>,
+) {
+    let theme = settings.theme.get();
+    let hovered_token = tokens.iter().find(|(&interaction, _, _, _)| interaction != Interaction::None);
+    let hovered_sentence = match hovered_token {
+        Some((_, _, _, &ChildOf(sentence))) => sentences.get(sentence).ok(),
+        None => sentences.iter().find(|(_, &interaction, _, _)| interaction != Interaction::None),
+    };
+
+    let highlighted = match (hovered_token, hovered_sentence) {
+        (Some((_, &Value::Id(id), false, _)), _) => vec![(id, theme.highlight_primary)],
+        // A sentence with blank slots doesn't refer to anything yet.
+        (_, Some((entity, _, &sentence, &SentenceId(sentence_id)))) if board.filled(entity) =>
+            references(sentence, &board.values(entity), sentence_id, &board.truths(), theme),
+        _ => Vec::new(),
+    };
+
+    let checkboxes = board.checkboxes();
+    for (entity, _, _, &SentenceId(sentence_id)) in sentences.iter() {
+        let color = highlighted.iter().find(|&&(id, _)| id == sentence_id).map(|&(_, color)| color);
+        for entity in [entity, checkboxes[(sentence_id - 1) as usize]] {
+            let Ok(mut outline) = outlines.get_mut(entity) else { continue };
+            let highlight = match color {
+                Some(color) => Outline { width: HIGHLIGHT_WIDTH, color, ..*outline },
+                None => Outline { color: Color::NONE, ..*outline },
+            };
+            if *outline != highlight {
+                *outline = highlight;
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+use crate::game::{levels::CurrentLevel, reorder::rearrange, sentence::{exchange_blanks, travel, Blank, Value}, Checkbox};
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<History>()
+            .add_systems(Update, clear_history.run_if(resource_changed::<CurrentLevel>));
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Move {
+    Swap(Entity, Entity),
+    /// A checkbox together with the state it had before it was toggled.
+    Toggle(Entity, Option<bool>),
+    /// The sentence at the first position was moved to the second one.
+    Reorder(u32, u32),
+}
+
+/// Moves made on the current level, most recent last.
+#[derive(Resource, Default)]
+pub struct History(pub Vec<Move>);
+
+fn clear_history(mut history: ResMut<History>) {
+    history.0.clear();
+}
+
+pub fn undo(
+    mut commands: Commands,
+    mut history: ResMut<History>,
+    mut values: Query<(&mut Value, Has<Blank>)>,
+    mut checkboxes: Query<&mut Checkbox>,
+    nodes: Query<(&ComputedNode, &GlobalTransform)>,
+) {
+    match history.0.pop() {
+        Some(Move::Swap(entity1, entity2)) => {
+            let Ok([(mut value1, blank1), (mut value2, blank2)]) = values.get_many_mut([entity1, entity2]) else { return };
+            std::mem::swap(&mut *value1, &mut *value2);
+            exchange_blanks(&mut commands, (entity1, blank1), (entity2, blank2));
+            travel(&mut commands, &nodes, entity1, entity2);
+        }
+        Some(Move::Toggle(entity, previous)) => {
+            let Ok(mut checkbox) = checkboxes.get_mut(entity) else { return };
+            *checkbox = Checkbox(previous);
+        }
+        Some(Move::Reorder(from, to)) => {
+            commands.run_system_cached_with(rearrange, (to, from));
+        }
+        None => {}
+    }
+}
@@ -1,36 +1,67 @@
-use rand::{random, seq::SliceRandom, thread_rng};
+use rand::{random, seq::SliceRandom, thread_rng, Rng};
 use bevy::prelude::*;
 
-use crate::game::sentence::{Sentence, Value};
+use crate::{game::{sentence::{Sentence, Value}, solver::{arrangement, fixed_solutions, solutions, solvable}}, settings::Mode};
 
 pub struct LevelPlugin;
 
 impl Plugin for LevelPlugin {
     fn build(&self, app: &mut App) {
         app
-            .init_resource::<CurrentLevel>();
+            .init_resource::<CurrentLevel>()
+            .init_resource::<Goal>();
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct Level {
     pub sentences: Vec<SentenceDescription>,
+    pub goal: Goal,
+    /// Tokens waiting below the sentences in `Mode::TokenBank`. The sentences' own values then only give each
+    /// blank slot its kind.
+    pub bank: Vec<Value>,
+    /// Tokens that stay where they are, as (sentence index, slot). They are written in ink rather than pencil.
+    pub locked: Vec<(usize, usize)>,
+    /// Checkboxes that start out marked and can't be changed, as (sentence index, truth).
+    pub givens: Vec<(usize, bool)>,
 }
 
 impl Level {
-    pub fn get(_level_id: u32) -> Self {
-        return generate_level();
+    pub fn get(level_id: u32, mode: Mode) -> Self {
+        if mode == Mode::TruthOnly {
+            return generate_unique(sentence_count(level_id).min(MAX_TRUTH_ONLY_SENTENCES));
+        }
+        if mode == Mode::TokenBank {
+            return generate_level(sentence_count(level_id).min(MAX_BANK_SENTENCES)).into_bank();
+        }
+        if mode == Mode::Reorder {
+            return generate_solved(sentence_count(level_id).min(MAX_REORDER_SENTENCES)).scramble();
+        }
+        if level_id >= FIRST_PARADOX_LEVEL && thread_rng().gen_ratio(1, PARADOX_CHANCE) {
+            return generate_paradox(random::<u32>() as usize % 3 + 3);
+        }
+        if level_id >= FIRST_FIND_ALL_LEVEL && thread_rng().gen_ratio(1, FIND_ALL_CHANCE) {
+            return generate_find_all(random::<u32>() as usize % 3 + 3);
+        }
+        if level_id >= FIRST_GIVEN_LEVEL && thread_rng().gen_ratio(1, GIVEN_CHANCE) {
+            return generate_given(sentence_count(level_id).min(MAX_GIVEN_SENTENCES));
+        }
+        return generate_level(sentence_count(level_id));
     }
 
+    /// Splits the level into its sentences and pools of the tokens that can be moved, one pool per kind.
     pub fn take_apart(&self) -> (Vec<Sentence>, Vec<Value>, Vec<Value>, Vec<Value>) {
         let mut sentences = Vec::new();
         let mut ids = Vec::new();
         let mut numbers = Vec::new();
         let mut bools = Vec::new();
 
-        for sentence in self.sentences.iter() {
+        for (i, sentence) in self.sentences.iter().enumerate() {
             sentences.push(sentence.sentence);
-            for &value in sentence.values.iter() {
+            for (slot, &value) in sentence.values.iter().enumerate() {
+                if self.locked.contains(&(i, slot)) {
+                    continue;
+                }
                 match value {
                     Value::Id(_) => ids.push(value),
                     Value::Number(_) => numbers.push(value),
@@ -42,68 +73,73 @@ impl Level {
         (sentences, ids, numbers, bools)
     }
 
+    /// Deals the movable tokens of each kind out to the movable slots of that kind in a random order.
     pub fn shuffle(&self) -> Self {
-        let (sentences, mut ids, mut numbers, mut bools) = self.take_apart();
+        let (_, mut ids, mut numbers, mut bools) = self.take_apart();
 
         ids.shuffle(&mut thread_rng());
         numbers.shuffle(&mut thread_rng());
         bools.shuffle(&mut thread_rng());
 
-        let mut shuffled_sentences = Vec::new();
-
-        for sentence in sentences {
-            match sentence {
-                Sentence::IdSentenceIsBool => {
-                    let id = ids.pop().unwrap();
-                    let truth = bools.pop().unwrap();
-                    shuffled_sentences.push(SentenceDescription {
-                        sentence,
-                        values: vec![id, truth],
-                    });
-                },
-                Sentence::ThereAreNumberOfBoolSentences => {
-                    let number = numbers.pop().unwrap();
-                    let truth = bools.pop().unwrap();
-                    shuffled_sentences.push(SentenceDescription {
-                        sentence,
-                        values: vec![number, truth],
-                    });
-                },
-                Sentence::ClosestBoolIsNumberAway => {
-                    let truth = bools.pop().unwrap();
-                    let number = numbers.pop().unwrap();
-                    shuffled_sentences.push(SentenceDescription {
-                        sentence,
-                        values: vec![truth, number],
-                    });
-                },
-                Sentence::ThereAreNumberOfAlternatingGroups => {
-                    let number = numbers.pop().unwrap();
-                    shuffled_sentences.push(SentenceDescription {
-                        sentence,
-                        values: vec![number],
-                    });
-                },
-                Sentence::IdSentenceAndIdSentenceAreTheSame | Sentence::IdSentenceAndIdSentenceAreDifferent => {
-                    let id1 = ids.pop().unwrap();
-                    let id2 = ids.pop().unwrap();
-                    shuffled_sentences.push(SentenceDescription {
-                        sentence,
-                        values: vec![id1, id2],
-                    });
-                },
+        let mut shuffled = self.clone();
+        for (i, description) in shuffled.sentences.iter_mut().enumerate() {
+            for (slot, value) in description.values.iter_mut().enumerate() {
+                if self.locked.contains(&(i, slot)) {
+                    continue;
+                }
+                let pool = match value {
+                    Value::Id(_) => &mut ids,
+                    Value::Number(_) => &mut numbers,
+                    Value::Bool(_) => &mut bools,
+                };
+                *value = pool.pop().unwrap();
             }
         }
+        shuffled
+    }
 
-        Self {
-            sentences: shuffled_sentences,
+    /// Shuffles the order of the sentences, each keeping its own tokens. Ids still point at positions,
+    /// so the level is solved by finding an order in which every sentence can be marked consistently.
+    pub fn scramble(mut self) -> Self {
+        self.sentences.shuffle(&mut thread_rng());
+        self
+    }
+
+    /// Empties every slot into the bank and throws in a few decoys, which are extra tokens of the kinds the level uses.
+    /// Any consistent filling wins, so a decoy that happens to fit somewhere is fine too.
+    pub fn into_bank(mut self) -> Self {
+        let (_, ids, numbers, bools) = self.take_apart();
+        let n = self.sentences.len() as u32;
+        let mut bank = [ids, numbers, bools].concat();
+        for _ in 0..random::<u32>() % (MAX_DECOYS + 1) {
+            let decoy = match bank[random::<u32>() as usize % bank.len()] {
+                Value::Id(_) => Value::Id(random::<u32>() % n + 1),
+                Value::Bool(_) => Value::Bool(random::<bool>()),
+                Value::Number(_) => Value::Number(random::<u32>() % n + 1),
+            };
+            bank.push(decoy);
         }
+        bank.shuffle(&mut thread_rng());
+        self.bank = bank;
+        self
     }
 }
 
 #[derive(Resource, Default)]
 pub struct CurrentLevel(pub u32);
 
+/// What the player has to do to win a level. The level being played keeps its goal as a resource.
+#[derive(Resource, Clone, Default, PartialEq)]
+pub enum Goal {
+    /// Mark the checkboxes so that every sentence is consistent.
+    #[default]
+    Solve,
+    /// No arrangement of the tokens can be marked consistently, so the level is won by declaring a paradox.
+    Paradox,
+    /// Submit every one of these consistent markings, in any order.
+    FindAll(Vec<Vec<bool>>),
+}
+
 #[derive(Clone)]
 pub struct SentenceDescription {
     pub sentence: Sentence,
@@ -259,8 +295,20 @@ fn random_range_other_than(start: u32, stop: u32, other_than: u32) -> u32 {
     result
 }
 
-fn generate_level() -> Level {
-    let n = (random::<u32>() % 4 + 3) as usize;
+const MAX_SENTENCES: u32 = 24;
+
+/// Levels start with 3 to 6 sentences and grow by one every third level, spilling onto more notebook pages.
+fn sentence_count(level_id: u32) -> usize {
+    let minimum = 3 + level_id.saturating_sub(1) / 3;
+    (random::<u32>() % 4 + minimum).min(MAX_SENTENCES) as usize
+}
+
+fn generate_level(n: usize) -> Level {
+    generate_solved(n).shuffle()
+}
+
+/// A level as it is generated, with every token where it makes the sentences consistent.
+fn generate_solved(n: usize) -> Level {
     let mut solution_truths = Vec::<bool>::new();
     for _ in 0..n {
         solution_truths.push(random::<bool>());
@@ -271,7 +319,168 @@ fn generate_level() -> Level {
         solution_sentences.push(SentenceDescription::generate(solution_truths[i], i, &solution_truths));
     }
 
-    (Level {
+    Level {
         sentences: solution_sentences,
-    }).shuffle()
+        goal: Goal::Solve,
+        ..default()
+    }
+}
+
+const FIRST_PARADOX_LEVEL: u32 = 4;
+/// From `FIRST_PARADOX_LEVEL` on, one in this many levels is a paradox.
+const PARADOX_CHANCE: u32 = 5;
+
+/// Builds a level around "This sentence is false" and keeps only those the solver proves unsatisfiable,
+/// since swapping tokens around usually breaks the self-reference.
+/// Paradoxes have at most five sentences, which keeps trying every arrangement of the tokens cheap.
+fn generate_paradox(n: usize) -> Level {
+    loop {
+        let mut level = generate_level(n);
+        let liar = random::<u32>() as usize % n;
+        level.sentences[liar] = SentenceDescription {
+            sentence: Sentence::IdSentenceIsBool,
+            values: vec![Value::Id(liar as u32 + 1), Value::Bool(false)],
+        };
+        if !solvable(&level) {
+            level.goal = Goal::Paradox;
+            return level.shuffle();
+        }
+    }
+}
+
+const FIRST_FIND_ALL_LEVEL: u32 = 6;
+/// From `FIRST_FIND_ALL_LEVEL` on, one in this many levels asks for every solution.
+const FIND_ALL_CHANCE: u32 = 5;
+const MAX_FIND_ALL_SOLUTIONS: usize = 4;
+
+/// Keeps generating levels until one has a handful of different consistent markings, which the solver lists up front.
+fn generate_find_all(n: usize) -> Level {
+    loop {
+        let mut level = generate_level(n);
+        let solutions = solutions(&level);
+        if (2..=MAX_FIND_ALL_SOLUTIONS).contains(&solutions.len()) {
+            level.goal = Goal::FindAll(solutions);
+            return level;
+        }
+    }
+}
+
+/// Checking that a marking is the only one gets slow on long levels, and longer puzzles get tedious without swaps anyway.
+const MAX_TRUTH_ONLY_SENTENCES: usize = 12;
+
+/// Levels for `Mode::TruthOnly`, where the tokens stay where they are generated instead of being shuffled.
+/// The text alone has to pin down one marking, so sentences that can't tell it apart from another consistent marking
+/// are rewritten until no other one is left.
+fn generate_unique(n: usize) -> Level {
+    let truths = (0..n).map(|_| random::<bool>()).collect::<Vec<_>>();
+    let mut level = Level {
+        sentences: (0..n).map(|i| SentenceDescription::generate(truths[i], i, &truths)).collect(),
+        goal: Goal::Solve,
+        ..default()
+    };
+    while let Some(other) = fixed_solutions(&level, 2).into_iter().find(|solution| *solution != truths) {
+        let i = random::<u32>() as usize % n;
+        let sentence = SentenceDescription::generate(truths[i], i, &truths);
+        if sentence.sentence.evaluate(&sentence.values, i as u32 + 1, &other).truth != other[i] {
+            level.sentences[i] = sentence;
+        }
+    }
+    level
+}
+
+/// The bank has two ruled lines to itself, which holds the tokens of about this many sentences.
+const MAX_BANK_SENTENCES: usize = 8;
+const MAX_DECOYS: u32 = 3;
+
+/// Sentences can only be dragged around the page they are on, so reorder levels have to fit on one.
+const MAX_REORDER_SENTENCES: usize = 6;
+
+const FIRST_GIVEN_LEVEL: u32 = 3;
+/// From `FIRST_GIVEN_LEVEL` on, one in this many levels comes with givens.
+const GIVEN_CHANCE: u32 = 4;
+/// Listing every solution of a swap level gets slow past this many sentences, so levels with givens are kept shorter.
+const MAX_GIVEN_SENTENCES: usize = 6;
+/// When a given is added, one in this many times it is a token locked in place instead of a marked checkbox.
+const LOCKED_TOKEN_CHANCE: u32 = 3;
+
+/// A swap level whose only solution is the marking it was generated for. As long as other markings work too,
+/// a checkbox they disagree on is marked for the player, or a token is locked where that marking needs it.
+fn generate_given(n: usize) -> Level {
+    let truths = (0..n).map(|_| random::<bool>()).collect::<Vec<_>>();
+    let mut level = Level {
+        sentences: (0..n).map(|i| SentenceDescription::generate(truths[i], i, &truths)).collect(),
+        goal: Goal::Solve,
+        ..default()
+    }.shuffle();
+    loop {
+        let Some(other) = solutions(&level).into_iter().find(|solution| *solution != truths) else { return level };
+
+        let movable = (0..n)
+            .flat_map(|i| (0..level.sentences[i].values.len()).map(move |slot| (i, slot)))
+            .filter(|position| !level.locked.contains(position))
+            .collect::<Vec<_>>();
+        if !movable.is_empty() && thread_rng().gen_ratio(1, LOCKED_TOKEN_CHANCE) {
+            let arranged = arrangement(&level, &truths).unwrap();
+            let (i, slot) = movable[random::<u32>() as usize % movable.len()];
+            let wanted = arranged.sentences[i].values[slot];
+            // The token that belongs here trades places with whatever was here, so no token is lost.
+            let &(j, other_slot) = movable.iter()
+                .find(|&&(j, other_slot)| level.sentences[j].values[other_slot] == wanted)
+                .unwrap();
+            level.sentences[j].values[other_slot] = level.sentences[i].values[slot];
+            level.sentences[i].values[slot] = wanted;
+            level.locked.push((i, slot));
+        } else {
+            let disagreements = (0..n).filter(|&i| other[i] != truths[i]).collect::<Vec<_>>();
+            let i = disagreements[random::<u32>() as usize % disagreements.len()];
+            level.givens.push((i, truths[i]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_grow_every_third_level() {
+        for _ in 0..100 {
+            assert!((3..=6).contains(&sentence_count(1)));
+            assert!((4..=7).contains(&sentence_count(4)));
+            assert_eq!(sentence_count(1000), MAX_SENTENCES as usize);
+        }
+    }
+
+    #[test]
+    fn find_all_levels_list_their_solutions() {
+        for n in 3..=5 {
+            let level = generate_find_all(n);
+            let Goal::FindAll(listed) = &level.goal else { panic!("Expected Goal::FindAll") };
+            assert!((2..=MAX_FIND_ALL_SOLUTIONS).contains(&listed.len()));
+            assert_eq!(*listed, solutions(&level));
+        }
+    }
+
+    #[test]
+    fn bank_holds_every_token_and_a_few_decoys() {
+        let level = generate_level(5);
+        let tokens = level.sentences.iter().flat_map(|description| description.values.iter().copied()).collect::<Vec<_>>();
+        let mut bank = level.into_bank().bank;
+        assert!(bank.len() <= tokens.len() + MAX_DECOYS as usize);
+        for token in tokens.iter() {
+            let i = bank.iter().position(|value| value == token).expect("Every token of the level is in the bank");
+            bank.swap_remove(i);
+        }
+        for decoy in bank {
+            assert!(tokens.iter().any(|token| token.same_kind(&decoy)));
+        }
+    }
+
+    #[test]
+    fn given_levels_have_one_solution() {
+        for n in 3..=5 {
+            let level = generate_given(n);
+            assert_eq!(solutions(&level).len(), 1);
+        }
+    }
 }
@@ -1,46 +1,104 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
 
-use crate::{game::{levels::{CurrentLevel, LevelPlugin}, sentence::{evaluate_sentences, SentencePlugin}}, states::GameState};
+use crate::{game::{levels::{CurrentLevel, LevelPlugin}, sentence::{click_token, declare_paradox, evaluate_sentences, Evaluations, Locked, Sentence, SentencePlugin, Value, INK_WIDTH}, drag::DragPlugin, highlight::HighlightPlugin, history::{undo, History, HistoryPlugin, Move}, tooltip::{tooltip, TooltipPlugin}, view::{ViewPlugin, NOTEBOOK_SIZE}, page::{page_navigation, PagePlugin}, celebration::CelebrationPlugin, solutions::{solutions_panel, SolutionsPlugin}, bank::{token_bank, BankPlugin}, reorder::{click_sentence, ReorderPlugin}, tutorial::{tutorial_overlay, TutorialPlugin}}, focus::{Activate, Focusable}, settings::{Difficulty, Mode, Settings}, sound::{PlaySound, Sound}, states::GameState, theme::Theme, tween::{Animation, Property, Tween}};
 
 mod sentence;
 mod levels;
+mod evaluation;
+mod tooltip;
+mod highlight;
+mod drag;
+mod history;
+mod view;
+mod grammar;
+mod page;
+mod celebration;
+mod solver;
+mod solutions;
+mod bank;
+mod reorder;
+mod tutorial;
+
+pub use tutorial::Tutorial;
 
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_plugins((SentencePlugin, LevelPlugin))
+            .add_plugins((SentencePlugin, LevelPlugin, TooltipPlugin, HighlightPlugin, DragPlugin, HistoryPlugin, ViewPlugin, PagePlugin, CelebrationPlugin, SolutionsPlugin, BankPlugin, ReorderPlugin, TutorialPlugin))
             .add_systems(Startup, load_assets)
             .add_systems(OnEnter(GameState::InGame), (spawn_game, set_level).chain())
-            .add_systems(Update, (checkbox_interaction, evaluate_interaction))
+            .add_systems(Update, (checkbox_interaction, evaluate_interaction, declare_paradox_interaction, activate_element, update_checkbox_image))
+            .add_systems(Update, evaluate_sentences.run_if(in_state(GameState::InGame).and(evaluate_pressed)))
+            .add_systems(Update, declare_paradox.run_if(in_state(GameState::InGame).and(paradox_pressed)))
+            .add_systems(Update, undo.run_if(in_state(GameState::InGame).and(undo_pressed)))
+            .add_systems(Update, update_evaluations_left.run_if(resource_changed::<Evaluations>.or(resource_changed::<Settings>)))
             .add_systems(OnExit(GameState::InGame), despawn_game);
     }
 }
 
-const BACKGROUND_COLOR: Color = Color::srgb_u8(201, 241, 243);
-
 #[allow(dead_code)]
 #[derive(Resource)]
-struct CheckboxAssets(Handle<Image>, Handle<Image>);
+struct CheckboxAssets(Handle<Image>, Handle<Image>, Handle<Image>);
 
 fn load_assets(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
 ) {
+    let theme = settings.theme.get();
     commands.insert_resource(CheckboxAssets(
-        asset_server.load("checkbox_true.png"),
-        asset_server.load("checkbox_false.png"),
+        asset_server.load(theme.checkbox_true),
+        asset_server.load(theme.checkbox_false),
+        asset_server.load(theme.checkbox_unknown),
     ));
 }
 
 #[derive(Component)]
 struct Game;
 
+/// The mouse, or else the first touch, and the scales needed to place UI nodes at it.
+#[derive(SystemParam)]
+struct Pointer<'w> {
+    window: Single<'w, &'static Window>,
+    touches: Res<'w, Touches>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    ui_scale: Res<'w, UiScale>,
+}
+
+impl Pointer<'_> {
+    /// Pointer position in physical pixels, the space `ComputedNode` and `GlobalTransform` use.
+    fn physical_position(&self) -> Option<Vec2> {
+        self.window.physical_cursor_position()
+            .or_else(|| self.touches.first_pressed_position().map(|position| position * self.window.scale_factor()))
+    }
+
+    /// Converts physical pixels to the `Val::Px` units of UI nodes.
+    fn to_ui(&self, physical: Vec2) -> Vec2 {
+        physical / (self.window.scale_factor() * self.ui_scale.0)
+    }
+
+    fn just_pressed(&self) -> bool {
+        self.mouse.just_pressed(MouseButton::Left) || self.touches.any_just_pressed()
+    }
+
+    fn just_released(&self) -> bool {
+        self.mouse.just_released(MouseButton::Left) || self.touches.any_just_released()
+    }
+
+    fn scale_factor(&self) -> f32 {
+        self.window.scale_factor()
+    }
+}
+
 fn spawn_game(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    tutorial: Option<Res<Tutorial>>,
 ) {
+    let theme = settings.theme.get();
     commands.spawn((
         Game,
         Name::new("Background"),
@@ -48,13 +106,18 @@ fn spawn_game(
             width: Val::Percent(100.0),
             height: Val::Percent(100.0),
             flex_direction: FlexDirection::ColumnReverse,
+            justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
             ..default()
         },
-        BackgroundColor(BACKGROUND_COLOR),
+        BackgroundColor(theme.background),
     )).with_children(|commands| {
         commands
-            .spawn(notebook(asset_server.clone()));
+            .spawn(notebook(asset_server.clone(), &settings, settings.mode == Mode::Swap && tutorial.is_none()));
+        commands.spawn(tooltip(theme));
+        if tutorial.is_some() {
+            commands.spawn(tutorial_overlay(theme, &settings));
+        }
     });
 }
 
@@ -70,14 +133,24 @@ struct CheckboxContainer;
 #[derive(Component)]
 struct Evaluate;
 
-fn notebook(asset_server: AssetServer) -> impl Bundle {
+#[derive(Component)]
+struct EvaluationsLeft;
+
+#[derive(Component)]
+struct DeclareParadox;
+
+/// Paradoxes only come up when tokens can be swapped, and never in the tutorial's lessons.
+fn notebook(asset_server: AssetServer, settings: &Settings, paradoxes: bool) -> impl Bundle {
+    let theme = settings.theme.get();
+    let paradox_visibility = if paradoxes { Visibility::Inherited } else { Visibility::Hidden };
     (
         Name::new("Notebook"),
         Notebook,
-        ImageNode::new(asset_server.load("notebook.png")),
+        ImageNode::new(asset_server.load(theme.notebook)),
         Node {
-            width: Val::Px(1320.0),
-            height: Val::Px(753.0),
+            width: Val::Px(NOTEBOOK_SIZE.x),
+            height: Val::Px(NOTEBOOK_SIZE.y),
+            flex_shrink: 0.0,
             flex_direction: FlexDirection::Column,
             ..default()
         },
@@ -94,6 +167,7 @@ fn notebook(asset_server: AssetServer) -> impl Bundle {
                     bottom: Val::Px(6.0),
                     ..default()
                 },
+                related!(Children[token_bank(settings)]),
             ),
             (
                 Name::new("Check boxes"),
@@ -111,6 +185,7 @@ fn notebook(asset_server: AssetServer) -> impl Bundle {
             (
                 Name::new("Evaluate"),
                 Button,
+                Focusable,
                 Evaluate,
                 ImageNode::new(asset_server.load("play.png")),
                 Node {
@@ -122,6 +197,48 @@ fn notebook(asset_server: AssetServer) -> impl Bundle {
                     ..default()
                 },
             ),
+            (
+                Name::new("Evaluations left"),
+                EvaluationsLeft,
+                Text::new(""),
+                TextColor(theme.ink),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(133.0),
+                    right: Val::Px(270.0),
+                    ..default()
+                },
+            ),
+            (
+                Name::new("Declare paradox"),
+                Button,
+                Focusable,
+                DeclareParadox,
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(178.0),
+                    right: Val::Px(190.0),
+                    padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                    ..default()
+                },
+                BorderRadius::all(Val::Px(8.0)),
+                BackgroundColor(theme.ink.with_alpha(0.1)),
+                paradox_visibility,
+                related!(Children[(
+                    Text::new(settings.language.get("game.declare_paradox")),
+                    TextColor(theme.ink),
+                    TextFont {
+                        font_size: 18.0,
+                        ..default()
+                    },
+                )]),
+            ),
+            page_navigation(theme),
+            solutions_panel(theme),
         ]),
     )
 }
@@ -132,39 +249,152 @@ fn set_level(
     level.0 = 1;
 }
 
+/// Truth value the player assigned to a sentence, `None` until they have deduced it.
+#[derive(Component)]
+struct Checkbox(Option<bool>);
+
+impl Checkbox {
+    fn image(&self, theme: &Theme) -> &'static str {
+        match self.0 {
+            Some(true) => theme.checkbox_true,
+            Some(false) => theme.checkbox_false,
+            None => theme.checkbox_unknown,
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self.0 {
+            None => Checkbox(Some(true)),
+            Some(true) => Checkbox(Some(false)),
+            Some(false) => Checkbox(None),
+        }
+    }
+}
+
+#[derive(Component)]
+struct CheckboxImage;
+
+/// Dot next to a checkbox showing whether its sentence is currently consistent.
 #[derive(Component)]
-struct Checkbox(bool);
+struct ConsistencyMarker;
 
-fn checkbox(asset_server: AssetServer) -> impl Bundle {
+/// Checkboxes are drawn at 32px but react to the whole 48px line, which keeps them easy to hit on touch screens.
+/// A given checkbox starts out marked, inside an ink ring.
+fn checkbox(asset_server: AssetServer, theme: &Theme, given: Option<bool>) -> impl Bundle {
     (
         Name::new("Checkbox"),
         Button,
-        Checkbox(true),
-        ImageNode::new(asset_server.load("checkbox_true.png")),
+        Focusable,
+        Checkbox(given),
         Node {
-            width: Val::Px(32.0),
-            height: Val::Px(32.0),
-            margin: UiRect::new(Val::Px(0.0), Val::Px(0.0), Val::Px(8.0), Val::Px(8.0)),
+            width: Val::Px(48.0),
+            height: Val::Px(48.0),
+            margin: UiRect::left(Val::Px(-8.0)),
+            border: if given.is_some() { UiRect::all(INK_WIDTH) } else { UiRect::ZERO },
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
             ..default()
         },
+        BorderColor(theme.ink),
+        BorderRadius::MAX,
+        Outline::default(),
+        related!(Children[
+            (
+                Name::new("Checkbox image"),
+                CheckboxImage,
+                ImageNode::new(asset_server.load(Checkbox(given).image(theme))),
+                Node {
+                    width: Val::Px(32.0),
+                    height: Val::Px(32.0),
+                    ..default()
+                },
+            ),
+            (
+                Name::new("Consistency marker"),
+                ConsistencyMarker,
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(12.0),
+                    height: Val::Px(12.0),
+                    left: Val::Px(-14.0),
+                    top: Val::Px(18.0),
+                    ..default()
+                },
+                BorderRadius::MAX,
+                Visibility::Hidden,
+            ),
+        ]),
     )
 }
 
 fn checkbox_interaction(
-    interaction_query: Query<(&Interaction, &mut Checkbox, &mut ImageNode), (Changed<Interaction>, With<Button>)>,
+    mut commands: Commands,
+    interaction_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<Checkbox>, Without<Locked>)>,
+) {
+    for (entity, interaction) in interaction_query {
+        if *interaction == Interaction::Pressed {
+            commands.run_system_cached_with(toggle_checkbox, entity);
+        }
+    }
+}
+
+fn toggle_checkbox(
+    entity: In<Entity>,
+    mut checkboxes: Query<&mut Checkbox>,
+    mut history: ResMut<History>,
+    mut sounds: EventWriter<PlaySound>,
+) {
+    let mut checkbox = checkboxes.get_mut(*entity).unwrap();
+    history.0.push(Move::Toggle(*entity, checkbox.0));
+    *checkbox = checkbox.next();
+    sounds.write(PlaySound::new(Sound::Toggle));
+}
+
+const CHECKBOX_FLIP_DURATION: f32 = 0.2;
+
+/// Checkboxes flip over to show their new state, except when they first appear.
+fn update_checkbox_image(
+    mut commands: Commands,
+    checkboxes: Query<(Ref<Checkbox>, &Children), Changed<Checkbox>>,
+    mut images: Query<(Entity, &mut ImageNode), With<CheckboxImage>>,
     asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
 ) {
-    for (interaction, mut checkbox, mut image_node) in interaction_query {
-        match *interaction {
-            Interaction::Pressed => {
-                checkbox.0 = !checkbox.0;
-                *image_node = ImageNode::new(asset_server.load(if checkbox.0 {
-                    "checkbox_true.png"
-                } else {
-                    "checkbox_false.png"
-                }));
+    let edge_on = Vec2::new(0.0, 1.0);
+    let flip = Animation::new(Tween::new(Property::Scale(Vec2::ONE, edge_on), CHECKBOX_FLIP_DURATION / 2.0).ease(EaseFunction::QuadraticIn))
+        .then(Tween::new(Property::Scale(edge_on, Vec2::ONE), CHECKBOX_FLIP_DURATION / 2.0).ease(EaseFunction::QuadraticOut));
+    for (checkbox, children) in checkboxes {
+        let mut images = images.iter_many_mut(children);
+        while let Some((entity, mut image_node)) = images.fetch_next() {
+            *image_node = ImageNode::new(asset_server.load(checkbox.image(settings.theme.get())));
+            if !checkbox.is_added() {
+                commands.entity(entity).insert(flip.clone());
             }
-            _ => {}
+        }
+    }
+}
+
+/// Keyboard and gamepad counterpart of clicking on notebook elements.
+fn activate_element(
+    mut commands: Commands,
+    mut activations: EventReader<Activate>,
+    tokens: Query<(), With<Value>>,
+    sentences: Query<(), With<Sentence>>,
+    checkboxes: Query<(), With<Checkbox>>,
+    evaluate: Query<(), With<Evaluate>>,
+    declare_paradox_button: Query<(), With<DeclareParadox>>,
+) {
+    for &Activate(entity) in activations.read() {
+        if tokens.contains(entity) {
+            commands.run_system_cached_with(click_token, entity);
+        } else if sentences.contains(entity) {
+            commands.run_system_cached_with(click_sentence, entity);
+        } else if checkboxes.contains(entity) {
+            commands.run_system_cached_with(toggle_checkbox, entity);
+        } else if evaluate.contains(entity) {
+            commands.run_system_cached(evaluate_sentences);
+        } else if declare_paradox_button.contains(entity) {
+            commands.run_system_cached(declare_paradox);
         }
     }
 }
@@ -180,6 +410,55 @@ fn evaluate_interaction(
     }
 }
 
+fn declare_paradox_interaction(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<DeclareParadox>)>,
+) {
+    for &interaction in interaction_query.iter() {
+        if interaction == Interaction::Pressed {
+            commands.run_system_cached(declare_paradox);
+        }
+    }
+}
+
+fn evaluate_pressed(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+) -> bool {
+    keys.just_pressed(KeyCode::KeyE)
+        || gamepads.iter().any(|gamepad| gamepad.just_pressed(GamepadButton::North))
+}
+
+fn paradox_pressed(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+) -> bool {
+    keys.just_pressed(KeyCode::KeyP)
+        || gamepads.iter().any(|gamepad| gamepad.just_pressed(GamepadButton::West))
+}
+
+fn undo_pressed(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+) -> bool {
+    (keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) && keys.just_pressed(KeyCode::KeyZ))
+        || gamepads.iter().any(|gamepad| gamepad.just_pressed(GamepadButton::East))
+}
+
+fn update_evaluations_left(
+    evaluations: Res<Evaluations>,
+    settings: Res<Settings>,
+    mut text: Single<&mut Text, With<EvaluationsLeft>>,
+) {
+    text.0 = match (settings.difficulty, settings.evaluation_limit) {
+        (Difficulty::Hard, Some(limit)) => {
+            let language = settings.language;
+            language.format(language.get("game.evaluations_left"), &[&limit.saturating_sub(evaluations.0).to_string()])
+        }
+        _ => String::new(),
+    };
+}
+
 fn despawn_game(
     mut commands: Commands, 
     menu: Single<Entity, With<Game>>
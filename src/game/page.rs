@@ -0,0 +1,294 @@
+use bevy::prelude::*;
+
+use crate::{focus::{Activate, Focusable}, game::{bank::BANK_LINES, levels::CurrentLevel, sentence::{SentenceId, LINE_HEIGHT}, Checkbox, CheckboxContainer}, settings::{Mode, Settings}, states::GameState, theme::Theme};
+
+pub struct PagePlugin;
+
+impl Plugin for PagePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<CurrentPage>()
+            .init_resource::<Pages>()
+            .add_systems(Update, reset_page.run_if(resource_changed::<CurrentLevel>))
+            .add_systems(Update, (page_interaction, activate_page_button, page_keys, paginate, align_checkboxes).run_if(in_state(GameState::InGame)))
+            .add_systems(Update, (show_page, update_page_label).chain().after(paginate).run_if(
+                resource_changed::<CurrentPage>.or(resource_changed::<Pages>).or(any_match_filter::<Added<Checkbox>>)
+            ));
+    }
+}
+
+/// Number of ruled lines on a notebook page.
+pub const PAGE_LINES: u32 = 11;
+
+/// Index of the notebook page being shown.
+#[derive(Resource, Default)]
+pub struct CurrentPage(pub u32);
+
+/// The page each sentence is written on, indexed by sentence id - 1. Sentences never straddle two pages.
+#[derive(Resource, Default, PartialEq)]
+pub struct Pages(pub Vec<u32>);
+
+impl Pages {
+    pub fn page_of(&self, sentence_id: u32) -> u32 {
+        self.0.get((sentence_id - 1) as usize).copied().unwrap_or_default()
+    }
+
+    fn count(&self) -> u32 {
+        self.0.last().map_or(1, |&last| last + 1)
+    }
+
+    /// Fills pages of `page_lines` ruled lines with whole sentences in order, given how many lines each one takes up.
+    /// A sentence that doesn't fit on the current page starts the next one.
+    fn fill(lines: impl IntoIterator<Item = u32>, page_lines: u32) -> Self {
+        let mut pages = Pages::default();
+        let (mut current, mut used) = (0, 0);
+        for lines in lines {
+            if used > 0 && used + lines > page_lines {
+                current += 1;
+                used = 0;
+            }
+            used += lines;
+            pages.0.push(current);
+        }
+        pages
+    }
+}
+
+/// Flips the given number of pages when pressed.
+#[derive(Component)]
+struct PageButton(i32);
+
+#[derive(Component)]
+struct PageNavigation;
+
+#[derive(Component)]
+struct PageLabel;
+
+fn page_button(label: &str, pages: i32, theme: &Theme) -> impl Bundle {
+    (
+        Name::new(format!("Page button {label}")),
+        Button,
+        Focusable,
+        PageButton(pages),
+        Node {
+            width: Val::Px(40.0),
+            height: Val::Px(40.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BorderRadius::MAX,
+        BackgroundColor(theme.ink.with_alpha(0.1)),
+        related!(Children[(
+            Text::new(label),
+            TextColor(theme.ink),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+        )]),
+    )
+}
+
+pub fn page_navigation(theme: &Theme) -> impl Bundle {
+    (
+        Name::new("Page navigation"),
+        PageNavigation,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(125.0),
+            left: Val::Px(273.0),
+            column_gap: Val::Px(12.0),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        Visibility::Hidden,
+        related!(Children[
+            page_button("<", -1, theme),
+            (
+                PageLabel,
+                Text::new(""),
+                TextColor(theme.ink),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+            ),
+            page_button(">", 1, theme),
+        ]),
+    )
+}
+
+fn reset_page(mut page: ResMut<CurrentPage>) {
+    page.0 = 0;
+}
+
+fn turn_page(
+    flips: In<i32>,
+    mut page: ResMut<CurrentPage>,
+    pages: Res<Pages>,
+) {
+    let target = page.0.saturating_add_signed(*flips).min(pages.count() - 1);
+    if page.0 != target {
+        page.0 = target;
+    }
+}
+
+fn page_interaction(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &PageButton), Changed<Interaction>>,
+) {
+    for (interaction, &PageButton(pages)) in interaction_query {
+        if *interaction == Interaction::Pressed {
+            commands.run_system_cached_with(turn_page, pages);
+        }
+    }
+}
+
+fn activate_page_button(
+    mut commands: Commands,
+    mut activations: EventReader<Activate>,
+    buttons: Query<&PageButton>,
+) {
+    for &Activate(entity) in activations.read() {
+        if let Ok(&PageButton(pages)) = buttons.get(entity) {
+            commands.run_system_cached_with(turn_page, pages);
+        }
+    }
+}
+
+fn page_keys(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+) {
+    if keys.just_pressed(KeyCode::PageUp)
+        || gamepads.iter().any(|gamepad| gamepad.just_pressed(GamepadButton::LeftTrigger)) {
+        commands.run_system_cached_with(turn_page, -1);
+    }
+    if keys.just_pressed(KeyCode::PageDown)
+        || gamepads.iter().any(|gamepad| gamepad.just_pressed(GamepadButton::RightTrigger)) {
+        commands.run_system_cached_with(turn_page, 1);
+    }
+}
+
+/// Number of ruled lines a sentence wraps onto.
+fn lines(computed_node: &ComputedNode) -> u32 {
+    let height = computed_node.size().y * computed_node.inverse_scale_factor();
+    ((height / LINE_HEIGHT).round() as u32).max(1)
+}
+
+/// Lays the level's sentences out on pages as they wrap.
+fn paginate(
+    sentences: Query<(&SentenceId, &ComputedNode)>,
+    mut pages: ResMut<Pages>,
+    mut page: ResMut<CurrentPage>,
+    settings: Res<Settings>,
+) {
+    let page_lines = if settings.mode == Mode::TokenBank { PAGE_LINES - BANK_LINES } else { PAGE_LINES };
+    let mut sentences = sentences.iter().collect::<Vec<_>>();
+    sentences.sort_by_key(|(&SentenceId(id), _)| id);
+
+    let layout = Pages::fill(sentences.into_iter().map(|(_, computed_node)| lines(computed_node)), page_lines);
+
+    if *pages != layout {
+        *pages = layout;
+    }
+    if page.0 >= pages.count() {
+        page.0 = pages.count() - 1;
+    }
+}
+
+/// Off-page sentences are hidden but stay laid out at the text box's width, so their wrapped height is still known.
+fn show_page(
+    page: Res<CurrentPage>,
+    pages: Res<Pages>,
+    mut sentences: Query<(&SentenceId, &mut Node, &mut Visibility), Without<Checkbox>>,
+    checkbox_container: Single<&Children, With<CheckboxContainer>>,
+    mut checkboxes: Query<&mut Node, With<Checkbox>>,
+) {
+    let on_page = |sentence_id: u32| pages.page_of(sentence_id) == page.0;
+    for (&SentenceId(sentence_id), mut node, mut visibility) in sentences.iter_mut() {
+        if on_page(sentence_id) {
+            node.position_type = PositionType::Relative;
+            *visibility = Visibility::Inherited;
+        } else {
+            node.position_type = PositionType::Absolute;
+            node.left = Val::Px(0.0);
+            node.right = Val::Px(0.0);
+            *visibility = Visibility::Hidden;
+        }
+    }
+
+    let mut checkboxes = checkboxes.iter_many_mut(*checkbox_container);
+    let mut sentence_id = 1;
+    while let Some(mut node) = checkboxes.fetch_next() {
+        node.display = if on_page(sentence_id) { Display::Flex } else { Display::None };
+        sentence_id += 1;
+    }
+}
+
+/// Pads each checkbox to the height of its sentence, so the next one lines up with the next sentence.
+fn align_checkboxes(
+    sentences: Query<(&SentenceId, &ComputedNode), Changed<ComputedNode>>,
+    checkbox_container: Single<&Children, With<CheckboxContainer>>,
+    mut checkboxes: Query<&mut Node, With<Checkbox>>,
+) {
+    for (&SentenceId(sentence_id), computed_node) in sentences.iter() {
+        let Some(&checkbox) = checkbox_container.get((sentence_id - 1) as usize) else { continue };
+        let Ok(mut node) = checkboxes.get_mut(checkbox) else { continue };
+        let margin = Val::Px((lines(computed_node) - 1) as f32 * LINE_HEIGHT);
+        if node.margin.bottom != margin {
+            node.margin.bottom = margin;
+        }
+    }
+}
+
+fn update_page_label(
+    page: Res<CurrentPage>,
+    pages: Res<Pages>,
+    navigation: Single<&mut Visibility, With<PageNavigation>>,
+    mut label: Single<&mut Text, With<PageLabel>>,
+) {
+    let count = pages.count();
+    label.0 = format!("{}/{}", page.0 + 1, count);
+    *navigation.into_inner() = if count > 1 { Visibility::Inherited } else { Visibility::Hidden };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_pages_with_whole_sentences() {
+        let pages = Pages::fill([1; 12], PAGE_LINES);
+        assert_eq!(pages.0, [vec![0; 11], vec![1]].concat());
+        assert_eq!(pages.count(), 2);
+        assert_eq!(pages.page_of(12), 1);
+
+        // The fourth sentence would straddle the page break, so it starts the next page.
+        assert_eq!(Pages::fill([3, 3, 3, 3], 11).0, vec![0, 0, 0, 1]);
+        // A sentence taller than a page still gets one to itself.
+        assert_eq!(Pages::fill([2, 12, 1], 11).0, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn empty_level_has_one_page() {
+        let pages = Pages::default();
+        assert_eq!(pages.count(), 1);
+        assert_eq!(pages.page_of(1), 0);
+    }
+
+    #[test]
+    fn lines_round_to_ruled_lines() {
+        let node = |height: f32, inverse_scale_factor: f32| ComputedNode {
+            size: Vec2::new(300.0, height),
+            inverse_scale_factor,
+            ..default()
+        };
+        assert_eq!(lines(&node(0.0, 1.0)), 1);
+        assert_eq!(lines(&node(LINE_HEIGHT, 1.0)), 1);
+        assert_eq!(lines(&node(2.0 * LINE_HEIGHT + 3.0, 1.0)), 2);
+        assert_eq!(lines(&node(6.0 * LINE_HEIGHT, 0.5)), 3);
+    }
+}
@@ -0,0 +1,232 @@
+use bevy::prelude::*;
+
+use crate::{game::{celebration::Celebration, drag::node_rect, history::{History, Move}, levels::CurrentLevel, sentence::{PageTurn, Sentence, SentenceId}, CheckboxContainer, Pointer, TextBox}, settings::{Mode, Settings}, sound::{PlaySound, Sound}, states::GameState, tween::{Animation, Property, Tween}};
+
+pub struct ReorderPlugin;
+
+impl Plugin for ReorderPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<SentenceDrag>()
+            .add_systems(Update, clear_selection.run_if(resource_changed::<CurrentLevel>))
+            .add_systems(Update, (press_sentence, drag_sentence, drop_sentence).chain().run_if(
+                in_state(GameState::InGame).and(reordering)
+                    .and(not(any_with_component::<PageTurn>.or(any_with_component::<Celebration>)))
+            ));
+    }
+}
+
+/// How far the pointer has to travel, in logical pixels, before a press turns into a drag.
+const DRAG_THRESHOLD: f32 = 8.0;
+const SNAP_BACK_DURATION: f32 = 0.25;
+/// Background of the sentence that is picked up, or that a dragged sentence would be dropped on.
+const TINT_ALPHA: f32 = 0.2;
+
+/// The sentence being pressed or dragged in `Mode::Reorder`, and the one picked up by clicking.
+#[derive(Resource, Default)]
+pub struct SentenceDrag {
+    sentence: Option<Entity>,
+    dragging: bool,
+    /// Pointer positions are kept in physical pixels, the same space `ComputedNode` and `GlobalTransform` use.
+    start: Vec2,
+    position: Vec2,
+    selected: Option<Entity>,
+}
+
+fn reordering(settings: Res<Settings>) -> bool {
+    settings.mode == Mode::Reorder
+}
+
+type SentenceNodes<'w, 's> = Query<'w, 's, (Entity, &'static ComputedNode, &'static GlobalTransform, &'static InheritedVisibility), With<Sentence>>;
+
+/// The sentence on the current page under the pointer, looking past the one being dragged.
+/// Tokens would swallow `Interaction`, so sentences are found by position instead.
+fn sentence_at(sentences: &SentenceNodes, position: Vec2, dragged: Option<Entity>) -> Option<Entity> {
+    sentences.iter()
+        .find(|&(entity, node, transform, visibility)| {
+            Some(entity) != dragged && visibility.get() && node_rect(node, transform).contains(position)
+        })
+        .map(|(entity, _, _, _)| entity)
+}
+
+fn clear_selection(mut drag: ResMut<SentenceDrag>) {
+    drag.selected = None;
+}
+
+fn press_sentence(
+    mut drag: ResMut<SentenceDrag>,
+    pointer: Pointer,
+    sentences: SentenceNodes,
+) {
+    if !pointer.just_pressed() {
+        return;
+    }
+    let Some(position) = pointer.physical_position() else { return };
+    drag.sentence = sentence_at(&sentences, position, None);
+    drag.start = position;
+    drag.position = position;
+}
+
+/// The sentence itself follows the pointer, drawn above the others, and the one under the pointer is tinted.
+fn drag_sentence(
+    mut commands: Commands,
+    mut drag: ResMut<SentenceDrag>,
+    pointer: Pointer,
+    sentences: SentenceNodes,
+    mut nodes: Query<(&mut Node, &mut BackgroundColor), With<Sentence>>,
+    settings: Res<Settings>,
+) {
+    let theme = settings.theme.get();
+    let Some(sentence) = drag.sentence else { return };
+    let Some(position) = pointer.physical_position() else { return };
+    drag.position = position;
+
+    if !drag.dragging {
+        if position.distance(drag.start) < DRAG_THRESHOLD * pointer.scale_factor() {
+            return;
+        }
+        drag.dragging = true;
+        if let Some(selected) = drag.selected.take() {
+            if let Ok((_, mut color)) = nodes.get_mut(selected) {
+                color.0 = Color::NONE;
+            }
+        }
+        commands.entity(sentence).insert(ZIndex(1));
+    }
+
+    let offset = pointer.to_ui(position - drag.start);
+    if let Ok((mut node, _)) = nodes.get_mut(sentence) {
+        node.left = Val::Px(offset.x);
+        node.top = Val::Px(offset.y);
+    }
+
+    let target = sentence_at(&sentences, position, Some(sentence));
+    for (entity, _, _, _) in sentences.iter() {
+        let Ok((_, mut color)) = nodes.get_mut(entity) else { continue };
+        let tint = if Some(entity) == target { theme.highlight_primary.with_alpha(TINT_ALPHA) } else { Color::NONE };
+        if color.0 != tint {
+            color.0 = tint;
+        }
+    }
+}
+
+fn drop_sentence(
+    mut commands: Commands,
+    mut drag: ResMut<SentenceDrag>,
+    pointer: Pointer,
+    sentences: SentenceNodes,
+    ids: Query<&SentenceId>,
+    mut nodes: Query<(&mut Node, &mut BackgroundColor), With<Sentence>>,
+) {
+    if !pointer.just_released() {
+        return;
+    }
+    let Some(sentence) = drag.sentence.take() else { return };
+    if !std::mem::take(&mut drag.dragging) {
+        commands.run_system_cached_with(click_sentence, sentence);
+        return;
+    }
+
+    for (_, mut color) in nodes.iter_mut() {
+        color.0 = Color::NONE;
+    }
+    commands.entity(sentence).remove::<ZIndex>();
+    let target = sentence_at(&sentences, drag.position, Some(sentence));
+    let moved = match (ids.get(sentence), target.map(|target| ids.get(target))) {
+        (Ok(&SentenceId(from)), Some(Ok(&SentenceId(to)))) => {
+            commands.run_system_cached_with(move_sentence, (from, to));
+            true
+        }
+        _ => false,
+    };
+
+    let Ok((mut node, _)) = nodes.get_mut(sentence) else { return };
+    let offset = match (node.left, node.top) {
+        (Val::Px(left), Val::Px(top)) => Vec2::new(left, top),
+        _ => Vec2::ZERO,
+    };
+    if moved {
+        // The sentence is laid out in its new place right away, so it just drops in there.
+        node.left = Val::Px(0.0);
+        node.top = Val::Px(0.0);
+    } else {
+        let snap_back = Tween::new(Property::Position(offset, Vec2::ZERO), SNAP_BACK_DURATION).ease(EaseFunction::CubicOut);
+        commands.entity(sentence).insert(Animation::new(snap_back));
+    }
+}
+
+/// Picks the sentence up, or moves the one already picked up to its position.
+pub fn click_sentence(
+    sentence: In<Entity>,
+    mut commands: Commands,
+    mut drag: ResMut<SentenceDrag>,
+    ids: Query<&SentenceId>,
+    mut colors: Query<&mut BackgroundColor, With<Sentence>>,
+    mut sounds: EventWriter<PlaySound>,
+    settings: Res<Settings>,
+) {
+    match drag.selected.take() {
+        Some(selected) => {
+            if let Ok(mut color) = colors.get_mut(selected) {
+                color.0 = Color::NONE;
+            }
+            if let (Ok(&SentenceId(from)), Ok(&SentenceId(to))) = (ids.get(selected), ids.get(*sentence)) {
+                commands.run_system_cached_with(move_sentence, (from, to));
+            }
+        }
+        None => {
+            let Ok(mut color) = colors.get_mut(*sentence) else { return };
+            color.0 = settings.theme.get().highlight_primary.with_alpha(TINT_ALPHA);
+            drag.selected = Some(*sentence);
+            sounds.write(PlaySound::new(Sound::Select));
+        }
+    }
+}
+
+/// Takes the sentence at position `from` out and puts it back in at `to`, moving the ones in between over by one.
+pub fn move_sentence(
+    positions: In<(u32, u32)>,
+    mut commands: Commands,
+    mut history: ResMut<History>,
+    mut sounds: EventWriter<PlaySound>,
+) {
+    let (from, to) = *positions;
+    if from == to {
+        return;
+    }
+    commands.run_system_cached_with(rearrange, (from, to));
+    history.0.push(Move::Reorder(from, to));
+    sounds.write(PlaySound::new(Sound::Swap));
+}
+
+/// Renumbers the sentences and reorders both them and the checkboxes, so each mark stays with its sentence.
+pub fn rearrange(
+    positions: In<(u32, u32)>,
+    mut commands: Commands,
+    text_box: Single<(Entity, &Children), With<TextBox>>,
+    checkbox_container: Single<(Entity, &Children), With<CheckboxContainer>>,
+    mut sentences: Query<&mut SentenceId>,
+) {
+    let (from, to) = *positions;
+    let (from, to) = ((from - 1) as usize, (to - 1) as usize);
+
+    let (text_box, children) = text_box.into_inner();
+    let mut order = children.iter().filter(|&child| sentences.contains(child)).collect::<Vec<_>>();
+    order.sort_by_key(|&child| sentences.get(child).unwrap().0);
+    let moved = order.remove(from);
+    order.insert(to, moved);
+    for (i, &entity) in order.iter().enumerate() {
+        let mut sentence_id = sentences.get_mut(entity).unwrap();
+        if sentence_id.0 != i as u32 + 1 {
+            sentence_id.0 = i as u32 + 1;
+        }
+    }
+    order.extend(children.iter().filter(|&child| !sentences.contains(child)));
+    commands.entity(text_box).replace_children(&order);
+
+    let (checkbox_container, checkboxes) = checkbox_container.into_inner();
+    let mut checkboxes = checkboxes.to_vec();
+    let moved = checkboxes.remove(from);
+    checkboxes.insert(to, moved);
+    commands.entity(checkbox_container).replace_children(&checkboxes);
+}
@@ -1,6 +1,6 @@
-use bevy::{ecs::relationship::RelatedSpawnerCommands, prelude::*, text::LineHeight};
+use bevy::{ecs::{relationship::RelatedSpawnerCommands, system::SystemParam}, prelude::*, text::LineHeight};
 
-use crate::{game::{checkbox, levels::{CurrentLevel, Level, SentenceDescription}, Checkbox, CheckboxContainer, TextBox}, states::GameState};
+use crate::{focus::Focusable, game::{bank::fill_bank, celebration::{celebrate, Celebration}, checkbox, grammar::Segment, history::{History, Move}, levels::{CurrentLevel, Goal, Level, SentenceDescription}, solutions::submit_solution, tooltip::Tooltip, tutorial::{lesson_level, Tutorial}, Checkbox, CheckboxContainer, ConsistencyMarker, DeclareParadox, TextBox}, locale::Language, settings::{Difficulty, Mode, Settings}, sound::{PlaySound, Sound}, states::GameState, theme::Theme, tween::{Animation, AnimationFinished, Property, Tween}};
 
 pub struct SentencePlugin;
 
@@ -8,7 +8,13 @@ impl Plugin for SentencePlugin {
     fn build(&self, app: &mut App) {
         app
             .init_resource::<Selected>()
-            .add_systems(Update, (button_interaction, update_text, background_color_transition))
+            .add_systems(Update, (button_interaction, render_sentences))
+            .add_observer(turn_to_level)
+            .init_resource::<Evaluations>()
+            .add_systems(Update, (clear_explanations, validate_sentences).chain().run_if(
+                any_match_filter::<Changed<Value>>.or(any_match_filter::<Changed<Checkbox>>).or(any_match_filter::<Changed<SentenceId>>)
+                    .and(not(any_with_component::<PageTurn>.or(any_with_component::<Celebration>)))
+            ))
             .add_systems(Update, level_transition.run_if(resource_changed::<CurrentLevel>.and(in_state(GameState::InGame))));
     }
 }
@@ -21,42 +27,47 @@ pub enum Value {
 }
 
 impl Value {
-    fn to_string(&self, sentence_id: SentenceId) -> String {
-        match *self {
-            Value::Id(id) => {
-                if id == sentence_id.0 {
-                    "This".to_string()
-                } else if id % 10 == 1 {
-                    format!("{id}st")
-                } else if id % 10 == 2 {
-                    format!("{id}nd")
-                } else if id % 10 == 3 {
-                    format!("{id}rd")
-                } else {
-                    format!("{id}th")
-                }
-            }
-            Value::Bool(b) => format!("{b}"),
-            Value::Number(n) => format!(" {n} "),
+    pub fn same_kind(&self, other: &Value) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    pub fn color(&self, theme: &Theme) -> Color {
+        match self {
+            Value::Id(_) => theme.id_token,
+            Value::Bool(_) => theme.bool_token,
+            Value::Number(_) => theme.number_token,
         }
     }
-}
 
-impl Into<BackgroundColor> for Value {
-    fn into(self) -> BackgroundColor {
+    /// Each kind of token has its own silhouette, so kinds can be told apart without relying on color.
+    /// Borders only go on the sides, which keeps tokens on the ruled lines.
+    fn shape(&self) -> (BorderRadius, UiRect) {
         match self {
-            Value::Id(_) => BackgroundColor(Color::srgb_u8(225, 111, 124)),
-            Value::Bool(_) => BackgroundColor(Color::srgb_u8(225, 134, 0)),
-            Value::Number(_) => BackgroundColor(Color::srgb_u8(242, 220, 93)),
+            Value::Id(_) => (BorderRadius::MAX, UiRect::ZERO),
+            Value::Bool(_) => (BorderRadius::ZERO, UiRect::horizontal(Val::Px(3.0))),
+            Value::Number(_) => (BorderRadius::all(Val::Px(8.0)), UiRect::ZERO),
         }
     }
+
+    fn node(&self) -> (Node, BorderRadius) {
+        let (border_radius, border) = self.shape();
+        let node = Node {
+            padding: UiRect::horizontal(Val::Px(6.0)),
+            border,
+            ..default()
+        };
+        (node, border_radius)
+    }
 }
 
+/// Height of a ruled line in the notebook; sentences take up a whole number of them.
+pub const LINE_HEIGHT: f32 = 48.0;
+
 #[derive(Component, Clone, Copy)]
 pub struct SentenceId(pub u32);
 
 #[derive(Component, Debug, Clone, Copy, PartialEq)]
-#[require(Node)]
+#[require(Node, Interaction, Outline)]
 pub enum Sentence {
     IdSentenceIsBool,
     ThereAreNumberOfBoolSentences,
@@ -66,12 +77,66 @@ pub enum Sentence {
     IdSentenceAndIdSentenceAreDifferent,
 }
 
+const PAGE_TURN_DURATION: f32 = 0.2;
+/// How far, in radians, a page leans while it is being turned.
+const PAGE_TURN_TILT: f32 = 0.05;
+
+/// Marks the text box while the previous level is being turned away.
+#[derive(Component)]
+pub struct PageTurn;
+
+/// The notebook contents fold away like a turning page before the next level is written in.
 pub fn level_transition(
-    level: Res<CurrentLevel>,
+    mut evaluations: ResMut<Evaluations>,
     mut commands: Commands,
+    text_box: Single<Entity, With<TextBox>>,
+    checkbox_container: Single<Entity, With<CheckboxContainer>>,
+    sentences: Query<(), With<Sentence>>,
 ) {
+    evaluations.0 = 0;
+    if sentences.is_empty() {
+        commands.run_system_cached(open_page);
+        return;
+    }
+
+    let fold = Animation::new(Tween::new(Property::Scale(Vec2::ONE, Vec2::new(0.0, 1.0)), PAGE_TURN_DURATION).ease(EaseFunction::QuadraticIn))
+        .with(Tween::new(Property::Rotation(0.0, PAGE_TURN_TILT), PAGE_TURN_DURATION));
+    commands.entity(*text_box).insert((PageTurn, fold.clone()));
+    commands.entity(*checkbox_container).insert(fold);
+}
+
+fn turn_to_level(
+    trigger: Trigger<AnimationFinished>,
+    mut commands: Commands,
+    turning: Query<(), With<PageTurn>>,
+) {
+    if turning.contains(trigger.target()) {
+        commands.entity(trigger.target()).remove::<PageTurn>();
+        commands.run_system_cached(open_page);
+    }
+}
+
+fn open_page(
+    mut commands: Commands,
+    level: Res<CurrentLevel>,
+    settings: Res<Settings>,
+    text_box: Single<Entity, With<TextBox>>,
+    checkbox_container: Single<Entity, With<CheckboxContainer>>,
+    tutorial: Option<Res<Tutorial>>,
+) {
+    // The tutorial is over after its last lesson, and leaves the game instead.
+    let next = match tutorial {
+        Some(_) => lesson_level(level.0),
+        None => Some(Level::get(level.0, settings.mode)),
+    };
+    let Some(next) = next else { return };
     commands.run_system_cached(despawn_level);
-    commands.run_system_cached_with(spawn_level, Level::get(level.0).clone());
+    commands.run_system_cached_with(spawn_level, next);
+
+    let unfold = Animation::new(Tween::new(Property::Scale(Vec2::new(0.0, 1.0), Vec2::ONE), PAGE_TURN_DURATION).ease(EaseFunction::QuadraticOut))
+        .with(Tween::new(Property::Rotation(-PAGE_TURN_TILT, 0.0), PAGE_TURN_DURATION));
+    commands.entity(*text_box).insert(unfold.clone());
+    commands.entity(*checkbox_container).insert(unfold);
 }
 
 pub fn despawn_level(
@@ -93,7 +158,10 @@ pub fn spawn_level(
     checkbox_container: Single<Entity, With<CheckboxContainer>>,
     text_box: Single<Entity, With<TextBox>>,
     asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
 ) {
+    commands.insert_resource(level.goal.clone());
+    let theme = settings.theme.get();
     commands.entity(*text_box).with_children(|commands| {
         for (i, sentence) in level.sentences.iter().enumerate() {
             let sentence_id = SentenceId(i as u32 + 1);
@@ -101,165 +169,197 @@ pub fn spawn_level(
                 sentence_id.0, 
                 commands,
                 sentence.clone(),
+                theme,
+                settings.mode,
+                &level.locked.iter().filter(|&&(sentence, _)| sentence == i).map(|&(_, slot)| slot).collect::<Vec<_>>(),
             );
         }
     });
+    commands.run_system_cached_with(fill_bank, level.bank.clone());
 
     commands.entity(*checkbox_container).with_children(|commands| {
-        for _ in 0..level.sentences.len() {
-            commands.spawn(checkbox(asset_server.clone()));
+        for i in 0..level.sentences.len() {
+            let given = level.givens.iter().find(|&&(sentence, _)| sentence == i).map(|&(_, truth)| truth);
+            let mut checkbox = commands.spawn(checkbox(asset_server.clone(), theme, given));
+            if given.is_some() {
+                checkbox.insert(Locked).remove::<Focusable>();
+            }
         }
     });
 }
 
+/// Only the tokens are spawned here; `render_sentences` fills in the words around them.
+/// The mode decides what the player can pick up: tokens, blanks, or the whole sentence.
+/// Tokens in the `inked` slots are part of the puzzle and can't be moved in any mode.
 pub fn spawn_sentence(
     sentence_id: u32,
     commands: &mut RelatedSpawnerCommands<ChildOf>,
     sentence_description: SentenceDescription,
+    theme: &Theme,
+    mode: Mode,
+    inked: &[usize],
 ) {
     let SentenceDescription { sentence, values } = sentence_description;
-    match sentence {
-        Sentence::IdSentenceIsBool =>
-            commands.spawn((
-                Sentence::IdSentenceIsBool,
-                SentenceId(sentence_id),
-                related!(Children[
-                    plain_text(format!("{}. ", sentence_id)),
-                    text(values[0]),
-                    plain_text(" sentence is "),
-                    text(values[1]),
-                ]),
-            )),
-        Sentence::ThereAreNumberOfBoolSentences => 
-            commands.spawn((
-                Sentence::ThereAreNumberOfBoolSentences,
-                SentenceId(sentence_id),
-                related!(Children[
-                    plain_text(format!("{}. ", sentence_id)),
-                    plain_text("There are "),
-                    text(values[0]),
-                    plain_text(" sentences that are "),
-                    text(values[1]),
-                ]),
-            )),
-        Sentence::ClosestBoolIsNumberAway =>
-            commands.spawn((
-                Sentence::ClosestBoolIsNumberAway,
-                SentenceId(sentence_id),
-                related!(Children[
-                    plain_text(format!("{}. ", sentence_id)),
-                    plain_text("The closest "),
-                    text(values[0]),
-                    plain_text(" sentence is "),
-                    text(values[1]),
-                    plain_text(" spots away"),
-                ]),
-            )),
-        Sentence::ThereAreNumberOfAlternatingGroups =>
-            commands.spawn((
-                Sentence::ThereAreNumberOfAlternatingGroups,
-                SentenceId(sentence_id),
-                related!(Children[
-                    plain_text(format!("{}. ", sentence_id)),
-                    plain_text("There are "),
-                    text(values[0]),
-                    plain_text(" alternating groups"),
-                ]),
-            )),
-        Sentence::IdSentenceAndIdSentenceAreTheSame =>
-            commands.spawn((
-                Sentence::IdSentenceAndIdSentenceAreTheSame,
-                SentenceId(sentence_id),
-                related!(Children[
-                    plain_text(format!("{}. ", sentence_id)),
-                    plain_text("Both "),
-                    text(values[0]),
-                    plain_text(" and "),
-                    text(values[1]),
-                    plain_text(" sentences have the same truth value"),
-                ]),
-            )),
-        Sentence::IdSentenceAndIdSentenceAreDifferent =>
-            commands.spawn((
-                Sentence::IdSentenceAndIdSentenceAreDifferent,
-                SentenceId(sentence_id),
-                related!(Children[
-                    plain_text(format!("{}. ", sentence_id)),
-                    plain_text("Both "),
-                    text(values[0]),
-                    plain_text(" and "),
-                    text(values[1]),
-                    plain_text(" sentences have the opposite truth values"),
-                ]),
-            )),
-    };
+    let locked = matches!(mode, Mode::TruthOnly | Mode::Reorder);
+    let blank = mode == Mode::TokenBank;
+    let mut entity = commands.spawn((
+        sentence,
+        SentenceId(sentence_id),
+        Node {
+            flex_wrap: FlexWrap::Wrap,
+            ..default()
+        },
+    ));
+    if mode == Mode::Reorder {
+        entity.insert(Focusable);
+    }
+    entity.with_children(|commands| {
+        for (slot, value) in values.into_iter().enumerate() {
+            let mut token = commands.spawn((text(value, theme), Slot(slot)));
+            if locked {
+                token.insert(Locked).remove::<Focusable>();
+            }
+            if inked.contains(&slot) {
+                token.insert((Locked, Outline { width: INK_WIDTH, color: theme.ink, ..default() })).remove::<Focusable>();
+            } else if blank {
+                token.insert(Blank);
+            }
+        }
+    });
 }
 
-fn plain_text<S: Into<String> + Clone>(text: S) -> impl Bundle {
+/// A token the player can't move, or a checkbox they can't change.
+/// A token still reacts to hovering, which highlights the sentences it refers to.
+#[derive(Component)]
+pub struct Locked;
+
+/// Givens a level comes with are framed in ink, which sets them apart from what the player pencils in.
+pub const INK_WIDTH: Val = Val::Px(2.0);
+
+/// A slot, or a place in the bank, that no token has been put in. Its `Value` only tells which kind of token fits.
+#[derive(Component)]
+pub struct Blank;
+
+/// Position of a token among its sentence's values, which doesn't have to match where the language puts it.
+#[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Slot(pub usize);
+
+/// A plain word of a sentence, respawned whenever the sentence is rendered again.
+#[derive(Component)]
+struct Word;
+
+fn plain_text<S: Into<String> + Clone>(text: S, theme: &Theme) -> impl Bundle {
     (
         Name::new(text.clone().into()),
+        Word,
         Text::new(text),
-        TextColor(Color::BLACK),
+        TextColor(theme.ink),
         TextFont {
             font_size: 20.0,
-            line_height: LineHeight::Px(48.0),
+            line_height: LineHeight::Px(LINE_HEIGHT),
             ..default()
         },
     )
 }
 
-fn text(value: Value) -> impl Bundle {
-    let background_color: BackgroundColor = value.into();
+pub fn text(value: Value, theme: &Theme) -> impl Bundle {
     (
         Name::new("Text"),
         Text::new(""),
         Button,
+        Focusable,
         value,
-        TextColor(Color::BLACK),
+        TextColor(theme.ink),
         TextFont {
             font_size: 20.0,
-            line_height: LineHeight::Px(48.0),
+            line_height: LineHeight::Px(LINE_HEIGHT),
             ..default()
         },
-        BorderRadius::all(Val::Px(20.0)),
-        Outline { color: Color::BLACK, ..default()},
-        background_color,
+        value.node(),
+        BorderColor(theme.ink),
+        Outline { color: theme.ink, ..default()},
+        BackgroundColor(value.color(theme)),
     )
 }
 
-fn update_text(
-    value: Query<(Entity, &Value, &mut Text), Changed<Value>>,
-    child_of: Query<&ChildOf>,
-    sentence_ids: Query<&SentenceId>,
+pub const GHOST_OPACITY: f32 = 0.7;
+
+/// Semi-transparent copy of a token that follows the pointer while it is dragged.
+pub fn token_ghost(text: String, value: Value, theme: &Theme) -> impl Bundle {
+    let (mut node, border_radius) = value.node();
+    node.position_type = PositionType::Absolute;
+    (
+        Name::new("Ghost"),
+        Text::new(text),
+        TextColor(theme.ink.with_alpha(GHOST_OPACITY)),
+        TextFont {
+            font_size: 20.0,
+            line_height: LineHeight::Px(LINE_HEIGHT),
+            ..default()
+        },
+        node,
+        border_radius,
+        BorderColor(theme.ink.with_alpha(GHOST_OPACITY)),
+        BackgroundColor(value.color(theme).with_alpha(GHOST_OPACITY)),
+        GlobalZIndex(i32::MAX - 1),
+    )
+}
+
+/// Sentences wrap like a paragraph: every word is its own node, so a long sentence continues on the next ruled line.
+/// Swapping a token can change the grammar around it, so the words are rebuilt while the token entities stay.
+fn render_sentences(
+    mut commands: Commands,
+    changed: Query<&ChildOf, Changed<Value>>,
+    sentences: Query<(Entity, &Sentence, Ref<SentenceId>, &Children)>,
+    mut tokens: Query<(&Slot, &Value, Has<Blank>, &mut Text)>,
+    words: Query<(), With<Word>>,
+    settings: Res<Settings>,
 ) {
-    for (entity, &value, mut text) in value {
-        let &ChildOf(parent) = child_of.get(entity).unwrap();
-        let &sentence_id = sentence_ids.get(parent).unwrap();
-        *text = Text::new(value.to_string(sentence_id));
+    for (entity, sentence, sentence_id, children) in sentences.iter() {
+        // A sentence that moved may now refer to itself, or stop doing so.
+        if !settings.is_changed() && !sentence_id.is_changed() && !changed.iter().any(|&ChildOf(parent)| parent == entity) {
+            continue;
+        }
+        let SentenceId(sentence_id) = *sentence_id;
+
+        let mut slots = children.iter()
+            .filter_map(|child| tokens.get(child).ok().map(|(&slot, &value, blank, _)| (slot, (!blank).then_some(value), child)))
+            .collect::<Vec<_>>();
+        slots.sort_by_key(|&(slot, _, _)| slot);
+        let values = slots.iter().map(|&(_, value, _)| value).collect::<Vec<_>>();
+
+        let mut ordered = Vec::new();
+        for segment in sentence.render(&values, sentence_id, settings.language, settings.spelled_numbers) {
+            match segment {
+                Segment::Plain(text) => {
+                    for word in text.split_inclusive(' ') {
+                        ordered.push(commands.spawn(plain_text(word, settings.theme.get())).id());
+                    }
+                }
+                Segment::Token(slot, word) => {
+                    let (_, _, token) = slots[slot];
+                    tokens.get_mut(token).unwrap().3.0 = word;
+                    ordered.push(token);
+                }
+            }
+        }
+
+        for child in children.iter().filter(|&child| words.contains(child)) {
+            commands.entity(child).despawn();
+        }
+        commands.entity(entity).replace_children(&ordered);
     }
 }
 
 #[derive(Resource, Default, PartialEq, Debug)]
-struct Selected(Option<Entity>);
+pub struct Selected(pub Option<Entity>);
 
 fn button_interaction(
-    mut commands: Commands,
-    interaction_query: Query<(Entity, &Interaction, &mut BackgroundColor), (Changed<Interaction>, With<Value>)>,
-    mut selected: ResMut<Selected>,
-    mut outlines: Query<&mut Outline>,
+    interaction_query: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<Value>, Without<Locked>)>,
 ) {
-    for (entity, interaction, mut color) in interaction_query {
+    for (interaction, mut color) in interaction_query {
         match *interaction {
-            Interaction::Pressed => {
-                if let Some(selected_entity) = selected.0 {
-                    outlines.get_mut(selected_entity).unwrap().width = Val::Px(0.0);
-                    commands.run_system_cached_with(swap, (entity, selected_entity));
-                    selected.0 = None;
-                } else {
-                    selected.0 = Some(entity);
-                    outlines.get_mut(entity).unwrap().width = Val::Px(1.0);
-                }
-            }
+            Interaction::Pressed => {}
             Interaction::Hovered => {
                 color.0.set_alpha(0.5);
             }
@@ -270,16 +370,39 @@ fn button_interaction(
     }
 }
 
-fn swap(
+/// Selects the token, or swaps it with the already selected one.
+pub fn click_token(
+    token: In<Entity>,
+    mut commands: Commands,
+    mut selected: ResMut<Selected>,
+    mut outlines: Query<&mut Outline>,
+    mut sounds: EventWriter<PlaySound>,
+) {
+    if let Some(selected_entity) = selected.0 {
+        outlines.get_mut(selected_entity).unwrap().width = Val::Px(0.0);
+        commands.run_system_cached_with(swap, (*token, selected_entity));
+        selected.0 = None;
+    } else {
+        selected.0 = Some(*token);
+        outlines.get_mut(*token).unwrap().width = Val::Px(1.0);
+        sounds.write(PlaySound::new(Sound::Select));
+    }
+}
+
+pub fn swap(
     pair: In<(Entity, Entity)>,
-    mut value: Query<&mut Value>,
+    mut commands: Commands,
+    mut value: Query<(&mut Value, Has<Blank>)>,
+    mut history: ResMut<History>,
+    mut sounds: EventWriter<PlaySound>,
+    nodes: Query<(&ComputedNode, &GlobalTransform)>,
 ) {
     let (entity1, entity2) = *pair;
     if entity1 == entity2 {
         return;
     }
 
-    let [mut value1, mut value2] = value.get_many_mut([entity1, entity2]).unwrap();
+    let [(mut value1, blank1), (mut value2, blank2)] = value.get_many_mut([entity1, entity2]).unwrap();
 
     match (*value1, *value2) {
         (Value::Id(id1), Value::Id(id2)) => {
@@ -295,171 +418,305 @@ fn swap(
             *value2 = Value::Number(n1);
         }
         _ => {
+            // Wiggles both tokens sideways to reject the swap.
+            let shake = Tween::new(Property::Shake { amplitude: SHAKE_AMPLITUDE, frequency: SHAKE_FREQUENCY }, SHAKE_DURATION);
+            commands.entity(entity1).insert(Animation::new(shake));
+            commands.entity(entity2).insert(Animation::new(shake));
+            sounds.write(PlaySound::new(Sound::Reject));
             return;
         }
     }
+    exchange_blanks(&mut commands, (entity1, blank1), (entity2, blank2));
+    history.0.push(Move::Swap(entity1, entity2));
+    travel(&mut commands, &nodes, entity1, entity2);
+    sounds.write(PlaySound::new(Sound::Swap));
 }
 
-#[derive(Component)]
-struct BackgroundColorTransition {
-    start_color: Color,
-    end_color: Color,
-    timer: Timer,
-}
-
-impl BackgroundColorTransition {
-    pub fn new(start_color: Color, end_color: Color, duration: f32) -> Self {
-        Self {
-            start_color,
-            end_color,
-            timer: Timer::from_seconds(duration, TimerMode::Once),
+/// A blank moves along with the value it hides, so putting a token in a slot leaves a blank where the token was.
+pub fn exchange_blanks(commands: &mut Commands, (entity1, blank1): (Entity, bool), (entity2, blank2): (Entity, bool)) {
+    if blank1 == blank2 {
+        return;
+    }
+    for (entity, blank) in [(entity1, blank2), (entity2, blank1)] {
+        if blank {
+            commands.entity(entity).insert(Blank);
+        } else {
+            commands.entity(entity).remove::<Blank>();
         }
     }
 }
 
-pub fn evaluate_sentences(
-    mut commands: Commands,
-    sentences: Query<(Entity, &Sentence, &SentenceId)>,
-    children: Query<&Children>,
-    values: Query<&Value>,
-    checkbox_container: Single<&Children, With<CheckboxContainer>>,
-    check_boxes: Query<&Checkbox>,
-    mut current_level: ResMut<CurrentLevel>,
+pub const SHAKE_DURATION: f32 = 0.4;
+pub const SHAKE_AMPLITUDE: f32 = 6.0;
+pub const SHAKE_FREQUENCY: f32 = 40.0;
+const TRAVEL_DURATION: f32 = 0.3;
+
+/// Called once two tokens have exchanged values, so each value is seen moving from its old slot to its new one.
+pub fn travel(
+    commands: &mut Commands,
+    nodes: &Query<(&ComputedNode, &GlobalTransform)>,
+    entity1: Entity,
+    entity2: Entity,
 ) {
-    let mut all_consistent = true;
-    for (sentence_entity, &sentence, &SentenceId(sentence_id)) in sentences {
-        let texts = children.get(sentence_entity).unwrap().iter().collect::<Vec<_>>();
-        let checkboxes = (*checkbox_container).iter().collect::<Vec<_>>();
-        let truth = match sentence {
-            Sentence::IdSentenceIsBool => {
-                let &Value::Id(id) = values.get(texts[1]).unwrap() else { panic!("Expected Value::Id") };
-                let &Value::Bool(truth) = values.get(texts[3]).unwrap() else { panic!("Expected Value::Bool") };
-
-                let &Checkbox(target_truth) = check_boxes.get(checkboxes[(id - 1) as usize]).unwrap();
-
-                target_truth == truth
-            }
-            Sentence::ThereAreNumberOfBoolSentences => {
-                let &Value::Number(number) = values.get(texts[2]).unwrap() else { panic!("Expected Value::Number") };
-                let &Value::Bool(truth) = values.get(texts[4]).unwrap() else { panic!("Expected Value::Bool") };
-
-                let mut count = 0;
-                for &checkbox in checkboxes.iter() {
-                    let &Checkbox(target_truth) = check_boxes.get(checkbox).unwrap();
-                    if target_truth == truth {
-                        count += 1;
-                    }
-                }
-                count == number
-            }
-            Sentence::ClosestBoolIsNumberAway => {
-                let &Value::Bool(truth) = values.get(texts[2]).unwrap() else { panic!("Expected Value::Bool") };
-                let &Value::Number(n) = values.get(texts[4]).unwrap() else { panic!("Expected Value::Id") };
-
-                let all_within_range_are_not_truth = {
-                    let mut ret = true;
-                    let (start, stop) = (sentence_id.saturating_sub(n) + 1, sentence_id + n - 1);
-                    for i in start..=stop {
-                        if i > checkboxes.len() as u32 || i == sentence_id {
-                            continue;
-                        }
-
-                        let &Checkbox(target_truth) = check_boxes.get(checkboxes[(i - 1) as usize]).unwrap();
-                        if target_truth == truth {
-                            ret = false;
-                            break;
-                        }
-                    }
+    let Ok([(node1, transform1), (node2, transform2)]) = nodes.get_many([entity1, entity2]) else { return };
+    let distance = transform2.translation().truncate() - transform1.translation().truncate();
+    for (entity, from) in [(entity1, distance * node1.inverse_scale_factor), (entity2, -distance * node2.inverse_scale_factor)] {
+        let tween = Tween::new(Property::Position(from, Vec2::ZERO), TRAVEL_DURATION).ease(EaseFunction::CubicOut);
+        commands.entity(entity).insert(Animation::new(tween));
+    }
+}
 
-                    ret
-                };
+/// Read access to the sentences and checkboxes currently in the notebook.
+#[derive(SystemParam)]
+pub struct Board<'w, 's> {
+    sentences: Query<'w, 's, (Entity, &'static Sentence, &'static SentenceId)>,
+    children: Query<'w, 's, &'static Children>,
+    values: Query<'w, 's, (&'static Slot, &'static Value, Has<Blank>)>,
+    checkbox_container: Single<'w, &'static Children, With<CheckboxContainer>>,
+    check_boxes: Query<'w, 's, &'static Checkbox>,
+}
 
-                let one_at_distance_is_truth = {
-                    let mut ret = false;
-                    if sentence_id > n {
-                        let &Checkbox(target_truth) = check_boxes.get(checkboxes[(sentence_id - n - 1) as usize]).unwrap();
-                        if target_truth == truth {
-                            ret = true;
-                        }
-                    }
-                    if sentence_id + n < checkboxes.len() as u32 + 1 {
-                        let &Checkbox(target_truth) = check_boxes.get(checkboxes[(sentence_id + n - 1) as usize]).unwrap();
-                        if target_truth == truth {
-                            ret = true;
-                        }
-                    }
+pub struct Verdict {
+    pub sentence: Entity,
+    pub sentence_id: u32,
+    /// What the player marked the sentence as.
+    pub marked: Option<bool>,
+    /// What the sentence evaluates to, if the known checkboxes already decide it.
+    pub truth: Option<bool>,
+    pub explanation: Option<String>,
+    pub rule: &'static str,
+}
 
-                    ret
-                };
+impl Verdict {
+    /// The explanation, if there is one, comes before the rule it follows from.
+    pub fn tooltip(&self) -> Tooltip {
+        match &self.explanation {
+            Some(explanation) => Tooltip(format!("{explanation}\n\n{}", self.rule)),
+            None => Tooltip(self.rule.to_string()),
+        }
+    }
 
-                all_within_range_are_not_truth && one_at_distance_is_truth
-            }
-            Sentence::ThereAreNumberOfAlternatingGroups => {
-                let &Value::Number(number) = values.get(texts[2]).unwrap() else { panic!("Expected Value::Number") };
+    pub fn consistent(&self) -> bool {
+        self.marked.is_some() && self.marked == self.truth
+    }
+
+    pub fn contradicted(&self) -> bool {
+        matches!((self.marked, self.truth), (Some(marked), Some(truth)) if marked != truth)
+    }
+
+    /// The sentence isn't marked yet, but the other checkboxes already decide its truth value.
+    pub fn determined(&self) -> bool {
+        self.marked.is_none() && self.truth.is_some()
+    }
+}
+
+impl Board<'_, '_> {
+    pub fn checkboxes(&self) -> Vec<Entity> {
+        self.checkbox_container.iter().collect()
+    }
+
+    pub fn truths(&self) -> Vec<Option<bool>> {
+        self.check_boxes.iter_many(*self.checkbox_container).map(|&Checkbox(truth)| truth).collect()
+    }
 
-                let mut count = 0;
-                let mut last_truth = None;
+    /// Values of the sentence in slot order, the order `Sentence::evaluate` expects.
+    pub fn values(&self, sentence: Entity) -> Vec<Value> {
+        let mut values = self.values.iter_many(self.children.get(sentence).unwrap()).collect::<Vec<_>>();
+        values.sort_by_key(|&(&slot, _, _)| slot);
+        values.into_iter().map(|(_, &value, _)| value).collect()
+    }
+
+    /// Whether every slot of the sentence holds a token. Until then the sentence doesn't claim anything.
+    pub fn filled(&self, sentence: Entity) -> bool {
+        self.values.iter_many(self.children.get(sentence).unwrap()).all(|(_, _, blank)| !blank)
+    }
 
-                for &checkbox in checkboxes.iter() {
-                    let &Checkbox(target_truth) = check_boxes.get(checkbox).unwrap();
-                    if Some(target_truth) != last_truth {
-                        count += 1;
-                        last_truth = Some(target_truth);
+    /// Evaluates every sentence against the checkboxes, in notebook order.
+    pub fn verdicts(&self, language: Language) -> Vec<Verdict> {
+        let truths = self.truths();
+        let known_truths = truths.iter().copied().collect::<Option<Vec<_>>>();
+        let mut verdicts = self.sentences.iter()
+            .map(|(entity, &sentence, &SentenceId(sentence_id))| {
+                let values = self.values(entity);
+                let marked = truths[(sentence_id - 1) as usize];
+                let rule = sentence.rule(language);
+                if !self.filled(entity) {
+                    return Verdict { sentence: entity, sentence_id, marked, truth: None, explanation: None, rule };
+                }
+                let truth = sentence.evaluate_partial(&values, sentence_id, &truths);
+                let explanation = match (&known_truths, truth) {
+                    (_, Some(truth)) if marked == Some(truth) => None,
+                    (Some(known_truths), _) => {
+                        let evaluation = sentence.evaluate(&values, sentence_id, known_truths);
+                        Some(sentence.explain(&values, sentence_id, &evaluation, language))
                     }
+                    (None, Some(truth)) => Some(language.format(language.get("explain.forced"), &[&truth.to_string()])),
+                    (None, None) => None,
+                };
+                Verdict {
+                    sentence: entity,
+                    sentence_id,
+                    marked,
+                    truth,
+                    explanation,
+                    rule,
                 }
+            })
+            .collect::<Vec<_>>();
+        verdicts.sort_by_key(|verdict| verdict.sentence_id);
+        verdicts
+    }
+}
 
-                count == number
-            }
-            Sentence::IdSentenceAndIdSentenceAreTheSame => {
-                let &Value::Id(id1) = values.get(texts[2]).unwrap() else { panic!("Expected Value::Id") };
-                let &Value::Id(id2) = values.get(texts[4]).unwrap() else { panic!("Expected Value::Id") };
-
-                let &Checkbox(target_truth1) = check_boxes.get(checkboxes[(id1 - 1) as usize]).unwrap();
-                let &Checkbox(target_truth2) = check_boxes.get(checkboxes[(id2 - 1) as usize]).unwrap();
+/// The truth values the checkboxes are marked with, once every verdict is consistent.
+fn marking(verdicts: &[Verdict]) -> Vec<bool> {
+    verdicts.iter().filter_map(|verdict| verdict.marked).collect()
+}
 
-                target_truth1 == target_truth2
-            }
-            Sentence::IdSentenceAndIdSentenceAreDifferent => {
-                let &Value::Id(id1) = values.get(texts[2]).unwrap() else { panic!("Expected Value::Id") };
-                let &Value::Id(id2) = values.get(texts[4]).unwrap() else { panic!("Expected Value::Id") };
+/// Failed sentences each get their own sound, this many seconds apart.
+const FAIL_SOUND_INTERVAL: f32 = 0.15;
 
-                let &Checkbox(target_truth1) = check_boxes.get(checkboxes[(id1 - 1) as usize]).unwrap();
-                let &Checkbox(target_truth2) = check_boxes.get(checkboxes[(id2 - 1) as usize]).unwrap();
+/// Number of times `Evaluate` was used on the current level.
+#[derive(Resource, Default)]
+pub struct Evaluations(pub u32);
 
-                target_truth1 != target_truth2
-            }
+pub fn evaluate_sentences(
+    mut commands: Commands,
+    board: Board,
+    settings: Res<Settings>,
+    mut sounds: EventWriter<PlaySound>,
+    busy: Query<(), Or<(With<PageTurn>, With<Celebration>)>>,
+    blanks: Query<Entity, (With<Blank>, With<Slot>)>,
+) {
+    if !busy.is_empty() {
+        return;
+    }
+    // Sentences with blank slots can't be evaluated yet, which doesn't cost an evaluation.
+    if !blanks.is_empty() {
+        let shake = Tween::new(Property::Shake { amplitude: SHAKE_AMPLITUDE, frequency: SHAKE_FREQUENCY }, SHAKE_DURATION);
+        for entity in blanks.iter() {
+            commands.entity(entity).insert(Animation::new(shake));
+        }
+        sounds.write(PlaySound::new(Sound::Reject));
+        return;
+    }
+    let theme = settings.theme.get();
+    let verdicts = board.verdicts(settings.language);
+    let failures = verdicts.iter().filter(|verdict| verdict.contradicted());
+    for (i, _) in failures.enumerate() {
+        sounds.write(PlaySound::new(Sound::Fail).after(i as f32 * FAIL_SOUND_INTERVAL));
+    }
+    for verdict in verdicts.iter() {
+        let flash = if verdict.contradicted() {
+            theme.inconsistent
+        } else if verdict.determined() {
+            theme.determined
+        } else {
+            continue;
         };
+        commands.entity(verdict.sentence).insert((
+            Animation::new(Tween::new(Property::BackgroundColor(flash, Color::NONE), 1.0)),
+            verdict.tooltip(),
+        ));
+    }
 
-        let &Checkbox(target_truth) = check_boxes.get(checkboxes[(sentence_id - 1) as usize]).unwrap();
-        let consistency = truth == target_truth;
+    // Unknown checkboxes are never consistent, so they block completion.
+    if verdicts.iter().all(Verdict::consistent) {
+        commands.run_system_cached_with(submit_solution, marking(&verdicts));
+    } else {
+        commands.run_system_cached(use_evaluation);
+    }
+}
 
-        if !consistency {
-            commands.entity(sentence_entity).insert(BackgroundColorTransition::new(Color::srgb(1.0, 0.0, 0.0), Color::NONE, 1.0));
-            all_consistent = false;
-        }
+/// Counts a failed `Evaluate` or paradox declaration, restarting the level once hard mode runs out of them.
+fn use_evaluation(
+    mut evaluations: ResMut<Evaluations>,
+    mut current_level: ResMut<CurrentLevel>,
+    settings: Res<Settings>,
+) {
+    evaluations.0 += 1;
+    if settings.difficulty == Difficulty::Hard && settings.evaluation_limit.is_some_and(|limit| evaluations.0 >= limit) {
+        info!("Out of evaluations, restarting level");
+        current_level.set_changed();
     }
+}
 
-    if all_consistent {
-        current_level.0 += 1;
+/// Wins a paradox level. On any other level it counts as a failed evaluation.
+/// The keyboard and gamepad shortcuts do nothing in modes that hide the button.
+pub fn declare_paradox(
+    mut commands: Commands,
+    goal: Res<Goal>,
+    button: Single<(Entity, &Visibility), With<DeclareParadox>>,
+    mut sounds: EventWriter<PlaySound>,
+    busy: Query<(), Or<(With<PageTurn>, With<Celebration>)>>,
+) {
+    let (button, visibility) = button.into_inner();
+    if !busy.is_empty() || *visibility == Visibility::Hidden {
+        return;
+    }
+    if *goal == Goal::Paradox {
+        commands.run_system_cached(celebrate);
+        sounds.write(PlaySound::new(Sound::Solve));
+    } else {
+        let shake = Tween::new(Property::Shake { amplitude: SHAKE_AMPLITUDE, frequency: SHAKE_FREQUENCY }, SHAKE_DURATION);
+        commands.entity(button).insert(Animation::new(shake));
+        sounds.write(PlaySound::new(Sound::Fail));
+        commands.run_system_cached(use_evaluation);
     }
 }
 
-fn background_color_transition(
+/// Recomputes consistency after every change when live validation is on.
+/// Only the markers are updated; the level is still submitted with `Evaluate`.
+fn validate_sentences(
     mut commands: Commands,
-    nodes: Query<(Entity, &mut BackgroundColorTransition, &mut BackgroundColor)>,
-    time: Res<Time>,
+    board: Board,
+    mut markers: Query<(&ChildOf, &mut BackgroundColor, &mut Visibility), With<ConsistencyMarker>>,
+    settings: Res<Settings>,
 ) {
-    let delta = time.delta();
-    for (entity, mut transition, mut color) in nodes {
-        if transition.timer.tick(delta).just_finished() {
-            commands.entity(entity).remove::<BackgroundColorTransition>();
-            *color = BackgroundColor(transition.end_color);
-            continue;
+    if !settings.live_validation {
+        for (_, _, mut visibility) in markers.iter_mut() {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    }
+
+    let checkboxes = board.checkboxes();
+    for verdict in board.verdicts(settings.language) {
+        commands.entity(verdict.sentence).insert(verdict.tooltip());
+
+        let theme = settings.theme.get();
+        let marker = if verdict.consistent() {
+            Some(theme.consistent)
+        } else if verdict.contradicted() {
+            Some(theme.inconsistent)
+        } else if verdict.determined() {
+            Some(theme.determined)
+        } else {
+            None
+        };
+        let checkbox = checkboxes[(verdict.sentence_id - 1) as usize];
+        for (_, mut color, mut visibility) in markers.iter_mut().filter(|(&ChildOf(parent), _, _)| parent == checkbox) {
+            match marker {
+                Some(marker) => {
+                    color.0 = marker;
+                    *visibility = Visibility::Inherited;
+                }
+                None => {
+                    *visibility = Visibility::Hidden;
+                }
+            }
         }
+    }
+}
 
-        let factor = transition.timer.fraction();
-        let BackgroundColorTransition { start_color, end_color, .. } = *transition;
-        let new_color = start_color.to_linear().mix(&end_color.to_linear(), factor).into();
-        *color = BackgroundColor(new_color);
+/// Explanations describe the board they were computed for, so they go stale as soon as the player changes anything.
+/// Only the rule of each sentence is left, which holds on any board.
+fn clear_explanations(
+    mut commands: Commands,
+    sentences: Query<(Entity, &Sentence)>,
+    settings: Res<Settings>,
+) {
+    for (entity, sentence) in sentences.iter() {
+        commands.entity(entity).insert(Tooltip(sentence.rule(settings.language).to_string()));
     }
 }
@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+
+use crate::{game::{celebration::celebrate, levels::{CurrentLevel, Goal}, sentence::{SHAKE_AMPLITUDE, SHAKE_DURATION, SHAKE_FREQUENCY}}, settings::Settings, sound::{PlaySound, Sound}, theme::Theme, tween::{Animation, Property, Tween}};
+
+pub struct SolutionsPlugin;
+
+impl Plugin for SolutionsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<FoundSolutions>()
+            .add_systems(Update, clear_solutions.run_if(resource_changed::<CurrentLevel>))
+            .add_systems(Update, update_solutions_panel.run_if(
+                resource_changed::<FoundSolutions>.or(resource_changed::<Goal>).or(resource_changed::<Settings>)
+            ));
+    }
+}
+
+/// Distinct consistent markings submitted so far on a `Goal::FindAll` level, in the order they were found.
+#[derive(Resource, Default)]
+pub struct FoundSolutions(pub Vec<Vec<bool>>);
+
+#[derive(Component)]
+struct SolutionsPanel;
+
+#[derive(Component)]
+struct SolutionsLabel;
+
+#[derive(Component)]
+struct SolutionStrips;
+
+/// One found solution, drawn as a row of cells: filled for true, hollow for false.
+#[derive(Component)]
+pub struct SolutionStrip(usize);
+
+const CELL_SIZE: f32 = 10.0;
+
+pub fn solutions_panel(theme: &Theme) -> impl Bundle {
+    (
+        Name::new("Solutions"),
+        SolutionsPanel,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(92.0),
+            left: Val::Px(480.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            ..default()
+        },
+        Visibility::Hidden,
+        related!(Children[
+            (
+                SolutionsLabel,
+                Text::new(""),
+                TextColor(theme.ink),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+            ),
+            (
+                SolutionStrips,
+                Node {
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                },
+            ),
+        ]),
+    )
+}
+
+fn clear_solutions(mut found: ResMut<FoundSolutions>) {
+    found.0.clear();
+}
+
+/// Called whenever every sentence on the board is consistent with the given marking.
+pub fn submit_solution(
+    truths: In<Vec<bool>>,
+    mut commands: Commands,
+    goal: Res<Goal>,
+    mut found: ResMut<FoundSolutions>,
+    strips: Query<(Entity, &SolutionStrip)>,
+    mut sounds: EventWriter<PlaySound>,
+) {
+    let Goal::FindAll(solutions) = &*goal else {
+        commands.run_system_cached(celebrate);
+        sounds.write(PlaySound::new(Sound::Solve));
+        return;
+    };
+
+    if let Some(index) = found.0.iter().position(|solution| *solution == *truths) {
+        let shake = Tween::new(Property::Shake { amplitude: SHAKE_AMPLITUDE, frequency: SHAKE_FREQUENCY }, SHAKE_DURATION);
+        for (entity, _) in strips.iter().filter(|&(_, &SolutionStrip(strip))| strip == index) {
+            commands.entity(entity).insert(Animation::new(shake));
+        }
+        sounds.write(PlaySound::new(Sound::Reject));
+        return;
+    }
+
+    found.0.push(truths.0);
+    sounds.write(PlaySound::new(Sound::Solve));
+    if found.0.len() == solutions.len() {
+        commands.run_system_cached(celebrate);
+    }
+}
+
+fn update_solutions_panel(
+    mut commands: Commands,
+    goal: Res<Goal>,
+    found: Res<FoundSolutions>,
+    panel: Single<&mut Visibility, With<SolutionsPanel>>,
+    mut label: Single<&mut Text, With<SolutionsLabel>>,
+    strips: Single<Entity, With<SolutionStrips>>,
+    settings: Res<Settings>,
+) {
+    let mut visibility = panel.into_inner();
+    let Goal::FindAll(solutions) = &*goal else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *visibility = Visibility::Inherited;
+
+    let language = settings.language;
+    label.0 = language.format(language.get("game.solutions_found"), &[&found.0.len().to_string(), &solutions.len().to_string()]);
+
+    let theme = settings.theme.get();
+    commands.entity(*strips).despawn_related::<Children>().with_children(|commands| {
+        for (index, solution) in found.0.iter().enumerate() {
+            commands.spawn((
+                Name::new("Solution"),
+                SolutionStrip(index),
+                Node {
+                    column_gap: Val::Px(2.0),
+                    ..default()
+                },
+            )).with_children(|commands| {
+                for &truth in solution {
+                    commands.spawn((
+                        Node {
+                            width: Val::Px(CELL_SIZE),
+                            height: Val::Px(CELL_SIZE),
+                            border: UiRect::all(Val::Px(1.5)),
+                            ..default()
+                        },
+                        BorderColor(theme.ink),
+                        BackgroundColor(if truth { theme.ink } else { Color::NONE }),
+                    ));
+                }
+            });
+        }
+    });
+}
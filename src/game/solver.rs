@@ -0,0 +1,234 @@
+use crate::game::{levels::Level, sentence::Value};
+
+/// Whether the level's tokens can be arranged and its checkboxes marked so that every sentence is consistent.
+pub fn solvable(level: &Level) -> bool {
+    markings(level).any(|truths| arrangement(level, &truths).is_some())
+}
+
+/// Every marking of the checkboxes that some arrangement of the level's tokens makes consistent.
+pub fn solutions(level: &Level) -> Vec<Vec<bool>> {
+    markings(level)
+        .filter(|truths| arrangement(level, truths).is_some())
+        .collect()
+}
+
+/// All ways to mark the level's checkboxes that agree with its givens.
+fn markings(level: &Level) -> impl Iterator<Item = Vec<bool>> + '_ {
+    let n = level.sentences.len();
+    (0..1u32 << n)
+        .map(move |mask| (0..n).map(|i| mask >> i & 1 == 1).collect::<Vec<_>>())
+        .filter(|truths| level.givens.iter().all(|&(i, truth)| truths[i] == truth))
+}
+
+/// Places the level's tokens so that every sentence evaluates to the truth value it is marked with, if that's possible.
+pub fn arrangement(level: &Level, truths: &[bool]) -> Option<Level> {
+    let mut search = Search {
+        level,
+        truths,
+        pool: pool(level),
+        values: level.sentences.iter().map(|description| description.values.clone()).collect(),
+    };
+    search.place(0, 0).then(|| {
+        let mut arranged = level.clone();
+        for (description, values) in arranged.sentences.iter_mut().zip(search.values) {
+            description.values = values;
+        }
+        arranged
+    })
+}
+
+/// Distinct values of the level's movable tokens and how many tokens carry each one.
+fn pool(level: &Level) -> Vec<(Value, usize)> {
+    let (_, ids, numbers, bools) = level.take_apart();
+    let mut pool = Vec::<(Value, usize)>::new();
+    for value in [ids, numbers, bools].concat() {
+        match pool.iter_mut().find(|(other, _)| *other == value) {
+            Some((_, count)) => *count += 1,
+            None => pool.push((value, 1)),
+        }
+    }
+    pool
+}
+
+/// Backtracking over token placements, one slot at a time in sentence order. Locked tokens are left where they are.
+/// A sentence is checked as soon as its last slot is filled, which prunes most placements early.
+struct Search<'a> {
+    level: &'a Level,
+    truths: &'a [bool],
+    /// Tokens not placed yet.
+    pool: Vec<(Value, usize)>,
+    values: Vec<Vec<Value>>,
+}
+
+impl Search<'_> {
+    fn place(&mut self, sentence: usize, slot: usize) -> bool {
+        if sentence == self.level.sentences.len() {
+            return true;
+        }
+        let description = &self.level.sentences[sentence];
+        if slot == description.values.len() {
+            let evaluation = description.sentence.evaluate(&self.values[sentence], sentence as u32 + 1, self.truths);
+            return evaluation.truth == self.truths[sentence] && self.place(sentence + 1, 0);
+        }
+
+        if self.level.locked.contains(&(sentence, slot)) {
+            return self.place(sentence, slot + 1);
+        }
+        let kind = description.values[slot];
+        for i in 0..self.pool.len() {
+            let (value, count) = self.pool[i];
+            if count == 0 || !value.same_kind(&kind) {
+                continue;
+            }
+            self.pool[i].1 -= 1;
+            self.values[sentence][slot] = value;
+            let placed = self.place(sentence, slot + 1);
+            self.pool[i].1 += 1;
+            if placed {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Markings that make every sentence consistent without moving any token, stopping once `limit` are found.
+pub fn fixed_solutions(level: &Level, limit: usize) -> Vec<Vec<bool>> {
+    let mut solutions = Vec::new();
+    let mut truths = vec![None; level.sentences.len()];
+    for &(i, truth) in level.givens.iter() {
+        truths[i] = Some(truth);
+    }
+    mark(level, truths, limit, &mut solutions);
+    solutions
+}
+
+/// Backtracking over checkbox markings. Sentences whose truth the marked checkboxes already decide get marked
+/// with it right away, and a branch is dropped as soon as a sentence contradicts its own checkbox.
+fn mark(level: &Level, mut truths: Vec<Option<bool>>, limit: usize, solutions: &mut Vec<Vec<bool>>) {
+    loop {
+        let mut forced = false;
+        for (i, description) in level.sentences.iter().enumerate() {
+            match (truths[i], description.sentence.evaluate_partial(&description.values, i as u32 + 1, &truths)) {
+                (Some(marked), Some(truth)) if marked != truth => return,
+                (None, Some(truth)) => {
+                    truths[i] = Some(truth);
+                    forced = true;
+                }
+                _ => {}
+            }
+        }
+        if !forced {
+            break;
+        }
+    }
+
+    match truths.iter().position(Option::is_none) {
+        None => solutions.push(truths.into_iter().flatten().collect()),
+        Some(index) => {
+            for truth in [false, true] {
+                if solutions.len() >= limit {
+                    return;
+                }
+                let mut branch = truths.clone();
+                branch[index] = Some(truth);
+                mark(level, branch, limit, solutions);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{levels::SentenceDescription, sentence::Sentence};
+
+    fn is_bool(id: u32, truth: bool) -> SentenceDescription {
+        SentenceDescription { sentence: Sentence::IdSentenceIsBool, values: vec![Value::Id(id), Value::Bool(truth)] }
+    }
+
+    fn level(sentences: Vec<SentenceDescription>) -> Level {
+        Level { sentences, ..Default::default() }
+    }
+
+    /// "1st sentence is true" and "1st sentence is false": whichever way the tokens go, the two sentences
+    /// end up marked opposite to each other.
+    fn two_markings() -> Level {
+        level(vec![is_bool(1, true), is_bool(1, false)])
+    }
+
+    #[test]
+    fn liar_is_not_solvable() {
+        let level = level(vec![is_bool(1, false)]);
+        assert!(!solvable(&level));
+        assert!(solutions(&level).is_empty());
+    }
+
+    #[test]
+    fn lists_every_solution() {
+        let level = two_markings();
+        assert_eq!(solutions(&level), vec![vec![true, false], vec![false, true]]);
+        assert_eq!(fixed_solutions(&level, usize::MAX), vec![vec![false, true], vec![true, false]]);
+    }
+
+    #[test]
+    fn stops_at_limit() {
+        assert_eq!(fixed_solutions(&two_markings(), 1).len(), 1);
+    }
+
+    #[test]
+    fn arranges_tokens_consistently() {
+        let level = two_markings();
+        assert!(solvable(&level));
+        for truths in [[true, true], [true, false], [false, true], [false, false]] {
+            let arranged = arrangement(&level, &truths);
+            assert_eq!(arranged.is_some(), truths[0] != truths[1]);
+            for (i, description) in arranged.iter().flat_map(|arranged| arranged.sentences.iter()).enumerate() {
+                assert_eq!(description.sentence.evaluate(&description.values, i as u32 + 1, &truths).truth, truths[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn respects_givens() {
+        let level = Level {
+            givens: vec![(0, true)],
+            ..two_markings()
+        };
+        assert_eq!(solutions(&level), vec![vec![true, false]]);
+        assert_eq!(fixed_solutions(&level, usize::MAX), vec![vec![true, false]]);
+
+        let contradicted = Level {
+            givens: vec![(0, true), (1, true)],
+            ..two_markings()
+        };
+        assert!(!solvable(&contradicted));
+        assert!(fixed_solutions(&contradicted, usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn never_moves_locked_tokens() {
+        // Unlocked, the first sentence can trade its false or its "this" away and stop being a liar.
+        let liar = level(vec![is_bool(1, false), is_bool(2, true), is_bool(3, false)]);
+        assert!(solvable(&liar));
+        let locked = Level {
+            locked: vec![(0, 0), (0, 1)],
+            ..liar
+        };
+        assert!(!solvable(&locked));
+
+        let level = Level {
+            locked: vec![(0, 0), (1, 1), (2, 0)],
+            ..level(vec![is_bool(2, true), is_bool(3, false), is_bool(1, false)])
+        };
+        let mut arranged = 0;
+        for truths in markings(&level) {
+            let Some(arrangement) = arrangement(&level, &truths) else { continue };
+            arranged += 1;
+            for &(i, slot) in level.locked.iter() {
+                assert_eq!(arrangement.sentences[i].values[slot], level.sentences[i].values[slot]);
+            }
+        }
+        assert!(arranged > 0);
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+
+use crate::{game::Pointer, theme::Theme};
+
+pub struct TooltipPlugin;
+
+impl Plugin for TooltipPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<LongPress>()
+            .add_systems(Update, (long_press, update_tooltip).chain());
+    }
+}
+
+/// Text shown next to the cursor while the entity is hovered. Needs an `Interaction` to be picked up.
+#[derive(Component, Clone)]
+pub struct Tooltip(pub String);
+
+#[derive(Component)]
+struct TooltipBox;
+
+const TOOLTIP_OFFSET: Vec2 = Vec2::new(16.0, 16.0);
+
+pub fn tooltip(theme: &Theme) -> impl Bundle {
+    (
+        Name::new("Tooltip"),
+        TooltipBox,
+        Text::new(""),
+        TextColor(theme.ink),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            max_width: Val::Px(400.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(theme.tooltip),
+        Outline { color: theme.ink, width: Val::Px(1.0), ..default() },
+        GlobalZIndex(i32::MAX),
+        Visibility::Hidden,
+    )
+}
+
+/// How long a touch has to rest on an element before its tooltip opens.
+const LONG_PRESS: f32 = 0.5;
+
+/// Touch screens can't hover, so their tooltips open on a long press and stay open until the next touch.
+#[derive(Resource, Default)]
+struct LongPress {
+    entity: Option<Entity>,
+    held: f32,
+    /// The entity whose tooltip is open, and where the touch was, in physical pixels.
+    pinned: Option<(Entity, Vec2)>,
+}
+
+fn long_press(
+    pressed: Query<(Entity, &Interaction), With<Tooltip>>,
+    mut long_press: ResMut<LongPress>,
+    pointer: Pointer,
+    time: Res<Time>,
+) {
+    if pointer.touches.any_just_pressed() {
+        *long_press = LongPress::default();
+    }
+    if pointer.touches.iter().next().is_none() {
+        long_press.entity = None;
+        return;
+    }
+
+    let entity = pressed.iter()
+        .find(|(_, &interaction)| interaction == Interaction::Pressed)
+        .map(|(entity, _)| entity);
+    if entity == long_press.entity {
+        long_press.held += time.delta_secs();
+    } else {
+        long_press.entity = entity;
+        long_press.held = 0.0;
+    }
+
+    if let (Some(entity), Some(position)) = (entity, pointer.physical_position()) {
+        if long_press.held >= LONG_PRESS && long_press.pinned.is_none() {
+            long_press.pinned = Some((entity, position));
+        }
+    }
+}
+
+fn update_tooltip(
+    tooltips: Query<(&Interaction, &Tooltip)>,
+    tooltip_box: Single<(&mut Node, &mut Text, &mut Visibility), With<TooltipBox>>,
+    long_press: Res<LongPress>,
+    pointer: Pointer,
+) {
+    let (mut node, mut text, mut visibility) = tooltip_box.into_inner();
+    let shown = match long_press.pinned {
+        Some((entity, position)) => tooltips.get(entity).ok().map(|(_, tooltip)| (tooltip, position)),
+        None if pointer.touches.iter().next().is_none() => tooltips.iter()
+            .find(|(&interaction, _)| interaction != Interaction::None)
+            .zip(pointer.physical_position())
+            .map(|((_, tooltip), position)| (tooltip, position)),
+        None => None,
+    };
+
+    match shown {
+        Some((Tooltip(tooltip), cursor)) => {
+            let position = pointer.to_ui(cursor) + TOOLTIP_OFFSET;
+            node.left = Val::Px(position.x);
+            node.top = Val::Px(position.y);
+            if text.0 != *tooltip {
+                text.0 = tooltip.clone();
+            }
+            *visibility = Visibility::Inherited;
+        }
+        None => {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use bevy::prelude::*;
+
+use crate::{focus::{Activate, Focusable}, game::{history::{History, Move}, levels::{CurrentLevel, Level, SentenceDescription}, sentence::{Evaluations, Sentence, SentenceId, Slot, Value}, CheckboxContainer, Evaluate}, settings::{Difficulty, Mode, Settings}, states::GameState, theme::Theme};
+
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, (restart_lesson.run_if(resource_changed::<CurrentLevel>), advance_tutorial, next_interaction, activate_next)
+                .chain()
+                .run_if(in_state(GameState::InGame).and(resource_exists::<Tutorial>)))
+            .add_systems(Update, (update_tutorial_panel, update_tutorial_pointer).run_if(in_state(GameState::InGame).and(resource_exists::<Tutorial>)))
+            .add_systems(OnExit(GameState::InGame), end_tutorial.run_if(resource_exists::<Tutorial>));
+    }
+}
+
+/// What the pointer ring is drawn around, by sentence index.
+#[derive(Clone, Copy)]
+enum Target {
+    None,
+    Sentence(usize),
+    Token(usize, usize),
+    Checkbox(usize),
+    Evaluate,
+}
+
+/// What the player has to do before the tutorial moves on.
+#[derive(Clone, Copy, PartialEq)]
+enum Wait {
+    /// Press the "Next" button.
+    Next,
+    Swap,
+    Toggle,
+    /// Press `Evaluate` while the board isn't solved yet. Solving it moves on to the next lesson anyway.
+    Evaluate,
+    /// Solve the level and press `Evaluate`, which moves on to the next lesson.
+    Solve,
+}
+
+struct Step {
+    text: &'static str,
+    target: Target,
+    wait: Wait,
+}
+
+/// A tiny level introducing one kind of sentence, always its first one.
+struct Lesson {
+    sentences: &'static [(Sentence, &'static [Value])],
+    locked: &'static [(usize, usize)],
+    givens: &'static [(usize, bool)],
+    steps: &'static [Step],
+}
+
+impl Lesson {
+    fn level(&self) -> Level {
+        Level {
+            sentences: self.sentences.iter()
+                .map(|&(sentence, values)| SentenceDescription { sentence, values: values.to_vec() })
+                .collect(),
+            locked: self.locked.to_vec(),
+            givens: self.givens.to_vec(),
+            ..default()
+        }
+    }
+}
+
+const LESSONS: [Lesson; 6] = [
+    Lesson {
+        sentences: &[
+            (Sentence::IdSentenceIsBool, &[Value::Id(1), Value::Bool(false)]),
+            (Sentence::IdSentenceIsBool, &[Value::Id(1), Value::Bool(true)]),
+        ],
+        locked: &[(0, 0), (1, 0)],
+        givens: &[],
+        steps: &[
+            Step { text: "tutorial.is_bool.1", target: Target::Sentence(0), wait: Wait::Next },
+            Step { text: "tutorial.is_bool.2", target: Target::Checkbox(0), wait: Wait::Next },
+            Step { text: "tutorial.is_bool.3", target: Target::Token(0, 1), wait: Wait::Swap },
+            Step { text: "tutorial.is_bool.4", target: Target::Checkbox(0), wait: Wait::Toggle },
+            Step { text: "tutorial.is_bool.5", target: Target::Checkbox(1), wait: Wait::Solve },
+        ],
+    },
+    Lesson {
+        sentences: &[
+            (Sentence::ThereAreNumberOfBoolSentences, &[Value::Number(1), Value::Bool(true)]),
+            (Sentence::IdSentenceIsBool, &[Value::Id(1), Value::Bool(false)]),
+            (Sentence::IdSentenceIsBool, &[Value::Id(2), Value::Bool(true)]),
+        ],
+        locked: &[(0, 0), (0, 1), (1, 0), (1, 1), (2, 0), (2, 1)],
+        givens: &[],
+        steps: &[
+            Step { text: "tutorial.count.1", target: Target::Sentence(0), wait: Wait::Next },
+            Step { text: "tutorial.count.2", target: Target::Evaluate, wait: Wait::Evaluate },
+            Step { text: "tutorial.count.3", target: Target::None, wait: Wait::Solve },
+        ],
+    },
+    Lesson {
+        sentences: &[
+            (Sentence::ClosestBoolIsNumberAway, &[Value::Bool(true), Value::Number(1)]),
+            (Sentence::IdSentenceIsBool, &[Value::Id(3), Value::Bool(false)]),
+            (Sentence::IdSentenceIsBool, &[Value::Id(2), Value::Bool(false)]),
+        ],
+        locked: &[(0, 0), (0, 1), (1, 0), (1, 1), (2, 0), (2, 1)],
+        givens: &[(1, false), (2, true)],
+        steps: &[
+            Step { text: "tutorial.closest.1", target: Target::Sentence(0), wait: Wait::Next },
+            Step { text: "tutorial.closest.2", target: Target::Checkbox(1), wait: Wait::Next },
+            Step { text: "tutorial.closest.3", target: Target::Checkbox(0), wait: Wait::Solve },
+        ],
+    },
+    Lesson {
+        sentences: &[
+            (Sentence::ThereAreNumberOfAlternatingGroups, &[Value::Number(2)]),
+            (Sentence::IdSentenceIsBool, &[Value::Id(2), Value::Bool(true)]),
+            (Sentence::IdSentenceIsBool, &[Value::Id(3), Value::Bool(true)]),
+            (Sentence::IdSentenceIsBool, &[Value::Id(4), Value::Bool(true)]),
+        ],
+        locked: &[(0, 0), (1, 0), (1, 1), (2, 0), (2, 1), (3, 0), (3, 1)],
+        givens: &[(1, true), (2, false), (3, true)],
+        steps: &[
+            Step { text: "tutorial.groups.1", target: Target::Sentence(0), wait: Wait::Next },
+            Step { text: "tutorial.groups.2", target: Target::Checkbox(0), wait: Wait::Solve },
+        ],
+    },
+    Lesson {
+        sentences: &[
+            (Sentence::IdSentenceAndIdSentenceAreTheSame, &[Value::Id(2), Value::Id(3)]),
+            (Sentence::IdSentenceIsBool, &[Value::Id(2), Value::Bool(true)]),
+            (Sentence::IdSentenceIsBool, &[Value::Id(3), Value::Bool(true)]),
+        ],
+        locked: &[(0, 0), (0, 1), (1, 0), (1, 1), (2, 0), (2, 1)],
+        givens: &[(1, true), (2, false)],
+        steps: &[
+            Step { text: "tutorial.same.1", target: Target::Sentence(0), wait: Wait::Next },
+            Step { text: "tutorial.same.2", target: Target::Checkbox(0), wait: Wait::Toggle },
+            Step { text: "tutorial.same.3", target: Target::Checkbox(0), wait: Wait::Solve },
+        ],
+    },
+    Lesson {
+        sentences: &[
+            (Sentence::IdSentenceAndIdSentenceAreDifferent, &[Value::Id(2), Value::Id(2)]),
+            (Sentence::IdSentenceIsBool, &[Value::Id(2), Value::Bool(true)]),
+            (Sentence::IdSentenceIsBool, &[Value::Id(3), Value::Bool(false)]),
+        ],
+        locked: &[(1, 0), (1, 1), (2, 1)],
+        givens: &[(0, true), (1, true), (2, false)],
+        steps: &[
+            Step { text: "tutorial.different.1", target: Target::Sentence(0), wait: Wait::Next },
+            Step { text: "tutorial.different.2", target: Target::Token(0, 1), wait: Wait::Swap },
+        ],
+    },
+];
+
+/// The level to play for `level_id` while the tutorial is running, one lesson per level.
+pub fn lesson_level(level_id: u32) -> Option<Level> {
+    LESSONS.get((level_id as usize).checked_sub(1)?).map(Lesson::level)
+}
+
+/// Present while the tutorial is being played.
+#[derive(Resource)]
+pub struct Tutorial {
+    lesson: usize,
+    step: usize,
+    /// Moves and failed evaluations when the step started, so only what the player does after reading it counts.
+    moves: usize,
+    evaluations: u32,
+    /// The player's own choices, put back once the tutorial is over. Lessons are played by swapping tokens
+    /// with live validation and no evaluation limit, so the consistency of every sentence is on show.
+    mode: Mode,
+    difficulty: Difficulty,
+    live_validation: bool,
+}
+
+impl Tutorial {
+    pub fn start(settings: &mut Settings) -> Self {
+        let tutorial = Self {
+            lesson: 0,
+            step: 0,
+            moves: 0,
+            evaluations: 0,
+            mode: settings.mode,
+            difficulty: settings.difficulty,
+            live_validation: settings.live_validation,
+        };
+        settings.mode = Mode::Swap;
+        settings.difficulty = Difficulty::Normal;
+        settings.live_validation = true;
+        tutorial
+    }
+
+    fn current(&self) -> Option<&'static Step> {
+        LESSONS.get(self.lesson).and_then(|lesson| lesson.steps.get(self.step))
+    }
+}
+
+#[derive(Component)]
+struct TutorialText;
+
+#[derive(Component)]
+struct TutorialNext;
+
+/// Ring drawn around whatever the current step is about.
+#[derive(Component)]
+struct TutorialPointer;
+
+/// The instructions float above the notebook, next to a button for steps that only need reading.
+pub fn tutorial_overlay(theme: &Theme, settings: &Settings) -> impl Bundle {
+    (
+        Name::new("Tutorial"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        GlobalZIndex(i32::MAX - 3),
+        related!(Children[
+            (
+                Name::new("Tutorial panel"),
+                Node {
+                    max_width: Val::Px(720.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    column_gap: Val::Px(16.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(theme.tooltip),
+                Outline { color: theme.ink, width: Val::Px(1.0), ..default() },
+                related!(Children[
+                    (
+                        TutorialText,
+                        Text::new(""),
+                        TextColor(theme.ink),
+                        TextFont {
+                            font_size: 18.0,
+                            ..default()
+                        },
+                    ),
+                    (
+                        Name::new("Next"),
+                        TutorialNext,
+                        Button,
+                        Focusable,
+                        Node {
+                            flex_shrink: 0.0,
+                            padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                            ..default()
+                        },
+                        BorderRadius::all(Val::Px(8.0)),
+                        BackgroundColor(theme.ink.with_alpha(0.1)),
+                        related!(Children[(
+                            Text::new(settings.language.get("tutorial.next")),
+                            TextColor(theme.ink),
+                            TextFont {
+                                font_size: 18.0,
+                                ..default()
+                            },
+                        )]),
+                    ),
+                ]),
+            ),
+            (
+                Name::new("Tutorial pointer"),
+                TutorialPointer,
+                Node {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                Outline { width: Val::Px(3.0), offset: Val::Px(4.0), color: theme.highlight_primary },
+                BorderRadius::all(Val::Px(8.0)),
+                Visibility::Hidden,
+            ),
+        ]),
+    )
+}
+
+/// Each lesson starts from its first step. Past the last lesson, the tutorial is over.
+fn restart_lesson(
+    mut tutorial: ResMut<Tutorial>,
+    current_level: Res<CurrentLevel>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let lesson = (current_level.0 as usize).saturating_sub(1);
+    if lesson >= LESSONS.len() {
+        next_state.set(GameState::MainMenu);
+    }
+    tutorial.lesson = lesson;
+    tutorial.step = 0;
+    // History and evaluations are cleared for the new level, so counting starts again from nothing.
+    tutorial.moves = 0;
+    tutorial.evaluations = 0;
+}
+
+fn advance_tutorial(
+    mut tutorial: ResMut<Tutorial>,
+    history: Res<History>,
+    evaluations: Res<Evaluations>,
+) {
+    let Some(step) = tutorial.current() else { return };
+    // Undone moves don't count towards the next one.
+    tutorial.moves = tutorial.moves.min(history.0.len());
+    let done = match step.wait {
+        Wait::Swap => history.0.len() > tutorial.moves && matches!(history.0.last(), Some(Move::Swap(..))),
+        Wait::Toggle => history.0.len() > tutorial.moves && matches!(history.0.last(), Some(Move::Toggle(..))),
+        Wait::Evaluate => evaluations.0 > tutorial.evaluations,
+        Wait::Next | Wait::Solve => false,
+    };
+    if done {
+        next_step(&mut tutorial, &history, &evaluations);
+    }
+}
+
+fn next_step(tutorial: &mut Tutorial, history: &History, evaluations: &Evaluations) {
+    tutorial.step += 1;
+    tutorial.moves = history.0.len();
+    tutorial.evaluations = evaluations.0;
+}
+
+fn next_interaction(
+    mut tutorial: ResMut<Tutorial>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<TutorialNext>)>,
+    history: Res<History>,
+    evaluations: Res<Evaluations>,
+) {
+    let pressed = interaction_query.iter().any(|&interaction| interaction == Interaction::Pressed);
+    if pressed && tutorial.current().is_some_and(|step| step.wait == Wait::Next) {
+        next_step(&mut tutorial, &history, &evaluations);
+    }
+}
+
+fn activate_next(
+    mut tutorial: ResMut<Tutorial>,
+    mut activations: EventReader<Activate>,
+    next: Query<(), With<TutorialNext>>,
+    history: Res<History>,
+    evaluations: Res<Evaluations>,
+) {
+    let activated = activations.read().any(|&Activate(entity)| next.contains(entity));
+    if activated && tutorial.current().is_some_and(|step| step.wait == Wait::Next) {
+        next_step(&mut tutorial, &history, &evaluations);
+    }
+}
+
+fn update_tutorial_panel(
+    tutorial: Res<Tutorial>,
+    mut text: Single<&mut Text, With<TutorialText>>,
+    next: Single<&mut Node, With<TutorialNext>>,
+    settings: Res<Settings>,
+) {
+    if !tutorial.is_changed() && !settings.is_changed() {
+        return;
+    }
+    let Some(step) = tutorial.current() else { return };
+    text.0 = settings.language.get(step.text).to_string();
+    next.into_inner().display = if step.wait == Wait::Next { Display::Flex } else { Display::None };
+}
+
+/// Follows the target every frame, since tokens move around and sentences wrap differently as they change.
+fn update_tutorial_pointer(
+    tutorial: Res<Tutorial>,
+    pointer: Single<(&mut Node, &mut Visibility), With<TutorialPointer>>,
+    sentences: Query<(Entity, &SentenceId, &Children)>,
+    slots: Query<&Slot>,
+    nodes: Query<(&ComputedNode, &GlobalTransform, &InheritedVisibility)>,
+    checkbox_container: Single<&Children, With<CheckboxContainer>>,
+    evaluate: Single<Entity, With<Evaluate>>,
+) {
+    let sentence = |index: usize| sentences.iter().find(|&(_, &SentenceId(id), _)| id as usize == index + 1);
+    let target = match tutorial.current().map_or(Target::None, |step| step.target) {
+        Target::None => None,
+        Target::Sentence(index) => sentence(index).map(|(entity, _, _)| entity),
+        Target::Token(index, slot) => sentence(index)
+            .and_then(|(_, _, children)| children.iter().find(|&child| slots.get(child).is_ok_and(|&Slot(other)| other == slot))),
+        Target::Checkbox(index) => checkbox_container.get(index).copied(),
+        Target::Evaluate => Some(*evaluate),
+    };
+
+    let (mut node, mut visibility) = pointer.into_inner();
+    let shown = target
+        .and_then(|entity| nodes.get(entity).ok())
+        .filter(|(computed_node, _, inherited_visibility)| inherited_visibility.get() && computed_node.size() != Vec2::ZERO);
+    let Some((computed_node, transform, _)) = shown else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let scale = computed_node.inverse_scale_factor();
+    let rect = Rect::from_center_size(transform.translation().truncate(), computed_node.size());
+    node.left = Val::Px(rect.min.x * scale);
+    node.top = Val::Px(rect.min.y * scale);
+    node.width = Val::Px(rect.width() * scale);
+    node.height = Val::Px(rect.height() * scale);
+    *visibility = Visibility::Inherited;
+}
+
+fn end_tutorial(
+    mut commands: Commands,
+    tutorial: Res<Tutorial>,
+    mut settings: ResMut<Settings>,
+) {
+    settings.mode = tutorial.mode;
+    settings.difficulty = tutorial.difficulty;
+    settings.live_validation = tutorial.live_validation;
+    commands.remove_resource::<Tutorial>();
+}
@@ -0,0 +1,141 @@
+use bevy::{input::mouse::{MouseScrollUnit, MouseWheel}, prelude::*};
+
+use crate::{game::Notebook, states::GameState};
+
+pub struct ViewPlugin;
+
+impl Plugin for ViewPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<NotebookView>()
+            .add_systems(Update, (pinch_zoom, wheel_scroll, apply_view).chain().run_if(in_state(GameState::InGame)))
+            .add_systems(Update, fit_to_window.after(apply_view).run_if(in_state(GameState::InGame)))
+            .add_systems(OnExit(GameState::InGame), reset_view);
+    }
+}
+
+/// Size of `notebook.png`, which every offset inside the notebook is measured against.
+pub const NOTEBOOK_SIZE: Vec2 = Vec2::new(1320.0, 753.0);
+
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 3.0;
+/// Pixels scrolled per wheel notch, one ruled line of the notebook.
+const LINE_SCROLL: f32 = 48.0;
+const WHEEL_ZOOM_SPEED: f32 = 0.002;
+
+/// Zoom and pan applied on top of the notebook layout, so it can be explored when it doesn't fit the screen.
+#[derive(Resource)]
+pub struct NotebookView {
+    pub zoom: f32,
+    /// Offset from the laid out position, in logical window pixels.
+    pub pan: Vec2,
+    /// The pan the current layout was computed with.
+    layout_pan: Vec2,
+}
+
+impl Default for NotebookView {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            pan: Vec2::ZERO,
+            layout_pan: Vec2::ZERO,
+        }
+    }
+}
+
+/// Two fingers pinch to zoom and move together to pan.
+fn pinch_zoom(
+    touches: Res<Touches>,
+    mut view: ResMut<NotebookView>,
+) {
+    let mut pressed = touches.iter();
+    let (Some(first), Some(second)) = (pressed.next(), pressed.next()) else { return };
+
+    let distance = first.position().distance(second.position());
+    let previous_distance = first.previous_position().distance(second.previous_position());
+    if previous_distance > 0.0 {
+        view.zoom = (view.zoom * distance / previous_distance).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    let midpoint = (first.position() + second.position()) / 2.0;
+    let previous_midpoint = (first.previous_position() + second.previous_position()) / 2.0;
+    view.pan += midpoint - previous_midpoint;
+}
+
+/// The wheel scrolls the notebook, or zooms it while Ctrl is held.
+fn wheel_scroll(
+    mut wheel: EventReader<MouseWheel>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut view: ResMut<NotebookView>,
+) {
+    for event in wheel.read() {
+        let delta = match event.unit {
+            MouseScrollUnit::Line => Vec2::new(event.x, event.y) * LINE_SCROLL,
+            MouseScrollUnit::Pixel => Vec2::new(event.x, event.y),
+        };
+        if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+            view.zoom = (view.zoom * (1.0 + delta.y * WHEEL_ZOOM_SPEED)).clamp(MIN_ZOOM, MAX_ZOOM);
+        } else {
+            view.pan += delta;
+        }
+    }
+}
+
+/// Pans are clamped so an overflowing notebook can be scrolled edge to edge, and one that fits stays put.
+fn clamp_pan(pan: f32, min: f32, max: f32, window: f32) -> f32 {
+    if max - min <= window {
+        0.0
+    } else {
+        pan.clamp(window - max, -min)
+    }
+}
+
+fn apply_view(
+    mut view: ResMut<NotebookView>,
+    ui_scale: Res<UiScale>,
+    notebook: Single<(&mut Node, &ComputedNode, &GlobalTransform), With<Notebook>>,
+    window: Single<&Window>,
+) {
+    let (mut node, computed_node, transform) = notebook.into_inner();
+
+    // Layout already includes the previous pan, so take it out to find where the notebook rests.
+    let center = transform.translation().truncate() / window.scale_factor() - view.layout_pan;
+    let rect = Rect::from_center_size(center, computed_node.size() / window.scale_factor());
+
+    let pan = Vec2::new(
+        clamp_pan(view.pan.x, rect.min.x, rect.max.x, window.width()),
+        clamp_pan(view.pan.y, rect.min.y, rect.max.y, window.height()),
+    );
+    if view.pan != pan || view.layout_pan != pan {
+        view.pan = pan;
+        view.layout_pan = pan;
+    }
+
+    node.left = Val::Px(pan.x / ui_scale.0);
+    node.top = Val::Px(pan.y / ui_scale.0);
+}
+
+/// Scales the whole UI so the notebook fills the window without changing its aspect ratio.
+///
+/// Everything inside the notebook is positioned in `notebook.png` pixels, so scaling it uniformly keeps the
+/// text on the ruled lines.
+fn fit_to_window(
+    view: Res<NotebookView>,
+    mut ui_scale: ResMut<UiScale>,
+    window: Single<&Window>,
+) {
+    let fit = (window.size() / NOTEBOOK_SIZE).min_element();
+    let scale = fit * view.zoom;
+    if scale > 0.0 && ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
+}
+
+/// The menus are laid out for an unscaled UI.
+fn reset_view(
+    mut view: ResMut<NotebookView>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    *view = NotebookView::default();
+    ui_scale.0 = 1.0;
+}
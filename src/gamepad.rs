@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use crate::focus::{Activate, Focus, MoveFocus};
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<StickRepeat>()
+            .add_systems(Update, gamepad_focus);
+    }
+}
+
+const STICK_THRESHOLD: f32 = 0.5;
+const STICK_REPEAT_DELAY: f32 = 0.35;
+const STICK_REPEAT_INTERVAL: f32 = 0.15;
+
+/// Turns a held stick into a stream of focus moves, like a held key.
+#[derive(Resource)]
+struct StickRepeat {
+    direction: Option<Vec2>,
+    timer: Timer,
+}
+
+impl Default for StickRepeat {
+    fn default() -> Self {
+        Self {
+            direction: None,
+            timer: Timer::from_seconds(STICK_REPEAT_DELAY, TimerMode::Once),
+        }
+    }
+}
+
+/// Snaps a stick position to one of the four screen directions (y points down), if it is pushed far enough.
+fn stick_direction(stick: Vec2) -> Option<Vec2> {
+    if stick.length() < STICK_THRESHOLD {
+        None
+    } else if stick.x.abs() > stick.y.abs() {
+        Some(Vec2::new(stick.x.signum(), 0.0))
+    } else {
+        Some(Vec2::new(0.0, -stick.y.signum()))
+    }
+}
+
+fn gamepad_focus(
+    gamepads: Query<&Gamepad>,
+    focus: Res<Focus>,
+    mut stick_repeat: ResMut<StickRepeat>,
+    time: Res<Time>,
+    mut moves: EventWriter<MoveFocus>,
+    mut activations: EventWriter<Activate>,
+) {
+    let mut stick = None;
+    for gamepad in gamepads.iter() {
+        for (button, direction) in [
+            (GamepadButton::DPadUp, Vec2::NEG_Y),
+            (GamepadButton::DPadDown, Vec2::Y),
+            (GamepadButton::DPadLeft, Vec2::NEG_X),
+            (GamepadButton::DPadRight, Vec2::X),
+        ] {
+            if gamepad.just_pressed(button) {
+                moves.write(MoveFocus(direction));
+            }
+        }
+
+        if gamepad.just_pressed(GamepadButton::South) {
+            match focus.0 {
+                Some(entity) => {
+                    activations.write(Activate(entity));
+                }
+                None => {
+                    moves.write(MoveFocus(Vec2::ZERO));
+                }
+            }
+        }
+
+        stick = stick.or(stick_direction(gamepad.left_stick()));
+    }
+
+    match stick {
+        Some(direction) if stick_repeat.direction != Some(direction) => {
+            moves.write(MoveFocus(direction));
+            stick_repeat.direction = Some(direction);
+            stick_repeat.timer = Timer::from_seconds(STICK_REPEAT_DELAY, TimerMode::Once);
+        }
+        Some(direction) => {
+            if stick_repeat.timer.tick(time.delta()).just_finished() {
+                moves.write(MoveFocus(direction));
+                stick_repeat.timer = Timer::from_seconds(STICK_REPEAT_INTERVAL, TimerMode::Once);
+            }
+        }
+        None => {
+            stick_repeat.direction = None;
+        }
+    }
+}
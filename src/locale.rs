@@ -0,0 +1,216 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+/// Languages the game is translated to. Each one has a `key = value` file in `assets/locales`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Polish,
+}
+
+pub type Strings = HashMap<&'static str, &'static str>;
+
+static ENGLISH: LazyLock<Strings> = LazyLock::new(|| parse_strings(include_str!("../assets/locales/en.txt")));
+static POLISH: LazyLock<Strings> = LazyLock::new(|| parse_strings(include_str!("../assets/locales/pl.txt")));
+
+/// Reads `key = value` lines, skipping blank lines and `#` comments. Values keep their edge spaces when quoted.
+pub fn parse_strings(source: &'static str) -> Strings {
+    source.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            (key.trim(), value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).unwrap_or(value))
+        })
+        .collect()
+}
+
+impl Language {
+    pub fn next(self) -> Self {
+        match self {
+            Language::English => Language::Polish,
+            Language::Polish => Language::English,
+        }
+    }
+
+    fn strings(self) -> &'static Strings {
+        match self {
+            Language::English => &ENGLISH,
+            Language::Polish => &POLISH,
+        }
+    }
+
+    fn lookup(self, key: &str) -> Option<&'static str> {
+        self.strings().get(key).copied()
+    }
+
+    /// The translation of `key`, falling back to English.
+    pub fn get(self, key: &str) -> &'static str {
+        self.lookup(key)
+            .or_else(|| Language::English.lookup(key))
+            .unwrap_or_else(|| {
+                bevy::log::warn_once!("Missing translation for {key}");
+                "???"
+            })
+    }
+
+    /// CLDR-style plural category of `n`: "one", "few" or "other".
+    pub fn plural_category(self, n: u32) -> &'static str {
+        match self {
+            Language::English => if n == 1 { "one" } else { "other" },
+            Language::Polish => match (n % 10, n % 100) {
+                _ if n == 1 => "one",
+                (2..=4, 12..=14) => "other",
+                (2..=4, _) => "few",
+                _ => "other",
+            },
+        }
+    }
+
+    /// Looks up `key.<category>` for the plural category of `n`, then `key.other` and `key`.
+    pub fn get_plural(self, key: &str, n: u32) -> &'static str {
+        [format!("{key}.{}", self.plural_category(n)), format!("{key}.other")]
+            .iter()
+            .find_map(|key| self.lookup(key).or_else(|| Language::English.lookup(key)))
+            .unwrap_or_else(|| self.get(key))
+    }
+
+    pub fn ordinal(self, n: u32) -> String {
+        match self {
+            Language::English => {
+                let suffix = match (n % 10, n % 100) {
+                    (_, 11..=13) => "th",
+                    (1, _) => "st",
+                    (2, _) => "nd",
+                    (3, _) => "rd",
+                    _ => "th",
+                };
+                format!("{n}{suffix}")
+            }
+            Language::Polish => format!("{n}."),
+        }
+    }
+
+    /// A word that may change with the grammatical context, like "true" after a number in Polish.
+    /// Looks up `word.<word>.<form>`, then `word.<word>`, and otherwise leaves the word as it is.
+    pub fn word(self, word: &str, form: Option<&str>) -> String {
+        form.and_then(|form| self.lookup(&format!("word.{word}.{form}")))
+            .or_else(|| self.lookup(&format!("word.{word}")))
+            .map_or_else(|| word.to_string(), str::to_string)
+    }
+
+    /// Spells out numbers below a hundred, which covers every count a level can ask about.
+    pub fn spelled(self, n: u32, form: Option<&str>) -> String {
+        let number = |n: u32| {
+            form.and_then(|form| self.lookup(&format!("number.{n}.{form}")))
+                .or_else(|| self.lookup(&format!("number.{n}")))
+        };
+        match (number(n), n) {
+            (Some(word), _) => word.to_string(),
+            (None, 20..100) => match (number(n / 10 * 10), number(n % 10)) {
+                (Some(tens), Some(ones)) => format!("{tens}{}{ones}", self.get("number.joiner")),
+                _ => format!("{n}"),
+            },
+            (None, _) => format!("{n}"),
+        }
+    }
+
+    /// Fills the `{0}`, `{1}`, ... placeholders of a template. `{0:form}` passes `arguments[0]` through `word`,
+    /// while `{0}` inserts it as it is, so text that is already translated can't be looked up again by accident.
+    pub fn format(self, template: &str, arguments: &[&str]) -> String {
+        parse_template(template)
+            .into_iter()
+            .map(|part| match part {
+                TemplatePart::Text(text) => text.to_string(),
+                TemplatePart::Slot(slot, None) => arguments[slot].to_string(),
+                TemplatePart::Slot(slot, form) => self.word(arguments[slot], form),
+            })
+            .collect()
+    }
+}
+
+pub enum TemplatePart<'a> {
+    Text(&'a str),
+    /// A placeholder for the argument in the given position, with an optional grammatical form.
+    Slot(usize, Option<&'a str>),
+}
+
+/// Splits a template into text and `{slot}` or `{slot:form}` placeholders. Slots can come in any order.
+pub fn parse_template(template: &str) -> Vec<TemplatePart<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(length) = rest[start..].find('}') else { break };
+        if start > 0 {
+            parts.push(TemplatePart::Text(&rest[..start]));
+        }
+        let placeholder = &rest[start + 1..start + length];
+        let (slot, form) = match placeholder.split_once(':') {
+            Some((slot, form)) => (slot, Some(form)),
+            None => (placeholder, None),
+        };
+        match slot.parse() {
+            Ok(slot) => parts.push(TemplatePart::Slot(slot, form)),
+            Err(_) => parts.push(TemplatePart::Text(&rest[start..=start + length])),
+        }
+        rest = &rest[start + length + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Text(rest));
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_strings() {
+        let strings = parse_strings("# A comment\n\nkey = value\nquoted = \" padded \"\n");
+        assert_eq!(strings.len(), 2);
+        assert_eq!(strings["key"], "value");
+        assert_eq!(strings["quoted"], " padded ");
+    }
+
+    #[test]
+    fn parses_templates() {
+        let parts = parse_template("{1:gen} jest {0}");
+        assert!(matches!(parts.as_slice(), [TemplatePart::Slot(1, Some("gen")), TemplatePart::Text(" jest "), TemplatePart::Slot(0, None)]));
+        let parts = parse_template("{x} and {");
+        assert!(matches!(parts.as_slice(), [TemplatePart::Text("{x}"), TemplatePart::Text(" and {")]));
+    }
+
+    #[test]
+    fn formats_words_only_when_asked() {
+        assert_eq!(Language::Polish.format("{0}", &["true"]), "true");
+        assert_eq!(Language::Polish.format("{0:word}", &["true"]), "prawdziwe");
+        assert_eq!(Language::Polish.format("{1:gen}, {0}", &["3", "false"]), "fałszywych, 3");
+        assert_eq!(Language::Polish.word("unknown", None), "unknown");
+    }
+
+    #[test]
+    fn plural_categories() {
+        let categories = |language: Language| [0, 1, 2, 4, 5, 12, 14, 22, 25, 112].map(|n| language.plural_category(n));
+        assert_eq!(categories(Language::English), ["other", "one", "other", "other", "other", "other", "other", "other", "other", "other"]);
+        assert_eq!(categories(Language::Polish), ["other", "one", "few", "few", "other", "other", "other", "few", "other", "other"]);
+        // Keys without plural forms are used for every count.
+        assert_eq!(Language::Polish.get_plural("sentence.is_bool", 5), Language::Polish.get("sentence.is_bool"));
+    }
+
+    #[test]
+    fn ordinals() {
+        let ordinals = [1, 2, 3, 4, 11, 12, 13, 21, 22, 23, 101, 111, 112].map(|n| Language::English.ordinal(n));
+        assert_eq!(ordinals, ["1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "22nd", "23rd", "101st", "111th", "112th"]);
+        assert_eq!(Language::Polish.ordinal(3), "3.");
+    }
+
+    #[test]
+    fn spells_numbers_below_a_hundred() {
+        let spelled = |language: Language, form: Option<&str>| [0, 1, 2, 13, 20, 42, 100].map(|n| language.spelled(n, form));
+        assert_eq!(spelled(Language::English, None), ["zero", "one", "two", "thirteen", "twenty", "forty-two", "100"]);
+        assert_eq!(spelled(Language::Polish, None), ["zero", "jeden", "dwa", "trzynaście", "dwadzieścia", "czterdzieści dwa", "100"]);
+        assert_eq!(spelled(Language::Polish, Some("fem"))[..3], ["zero", "jedna", "dwie"]);
+    }
+}
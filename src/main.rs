@@ -3,11 +3,24 @@ use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 mod menu;
 mod states;
 mod game;
+mod settings;
+mod focus;
+mod gamepad;
+mod locale;
+mod theme;
+mod sound;
+mod tween;
 
 
 use menu::MenuPlugin;
 use states::GameState;
 use game::GamePlugin;
+use settings::Settings;
+use focus::FocusPlugin;
+use gamepad::GamepadPlugin;
+use theme::ThemePlugin;
+use sound::SoundPlugin;
+use tween::TweenPlugin;
 
 fn main() {
   App::new()
@@ -19,7 +32,8 @@ fn main() {
         ..default()
     }))
     .init_state::<GameState>()
-    .add_plugins((MenuPlugin, GamePlugin))
+    .init_resource::<Settings>()
+    .add_plugins((MenuPlugin, GamePlugin, FocusPlugin, GamepadPlugin, ThemePlugin, SoundPlugin, TweenPlugin))
     .add_systems(Startup, spawn_camera)
     .add_systems(Update, app_exit.run_if(input_just_pressed(KeyCode::Escape)))
     .run();
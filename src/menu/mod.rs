@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::states::GameState;
+use crate::{focus::{Activate, Focusable}, game::Tutorial, settings::{next_volume, Difficulty, Mode, Settings, EVALUATION_LIMITS}, states::GameState};
 
 pub struct MenuPlugin;
 
@@ -9,17 +9,20 @@ impl Plugin for MenuPlugin {
         app
             .add_systems(OnEnter(GameState::MainMenu), spawn_menu)
             .add_systems(OnExit(GameState::MainMenu), despawn_menu)
-            .add_systems(Update, button_interaction);
+            .add_systems(Update, (button_interaction, activate_button))
+            .add_systems(Update, update_labels.run_if(resource_changed::<Settings>.and(in_state(GameState::MainMenu))));
     }
 }
 
-const BACKGROUND_COLOR: Color = Color::srgb_u8(201, 241, 243);
-
 #[derive(Component)]
 struct MainMenu;
 
-fn spawn_menu(mut commands: Commands) {
+fn spawn_menu(
+    mut commands: Commands,
+    settings: Res<Settings>,
+) {
     info!("Spawning main menu");
+    let theme = settings.theme.get();
     commands.spawn((
         MainMenu,
         Name::new("Background"),
@@ -31,12 +34,13 @@ fn spawn_menu(mut commands: Commands) {
             align_items: AlignItems::Center,
             ..default()
         },
-        BackgroundColor(BACKGROUND_COLOR),
+        BackgroundColor(theme.background),
     )).with_children(|commands| {
         commands.spawn((
             Name::new("Main Menu Text"),
-            Text::new("This sentence is false"),
-            TextColor(Color::BLACK),
+            MenuTitle,
+            Text::new(settings.language.get("menu.title")),
+            TextColor(theme.menu_text),
             TextFont {
                 font_size: 50.0,
                 ..default()
@@ -45,29 +49,90 @@ fn spawn_menu(mut commands: Commands) {
                 ..default()
             }
         ));
-        commands.spawn(button(Action::Play));
-        commands.spawn(button(Action::Quit));
+        commands.spawn(button(Action::Play, &settings));
+        commands.spawn(button(Action::Tutorial, &settings));
+        commands.spawn(button(Action::Mode, &settings));
+        commands.spawn(button(Action::Difficulty, &settings));
+        commands.spawn(button(Action::EvaluationLimit, &settings));
+        commands.spawn(button(Action::LiveValidation, &settings));
+        commands.spawn(button(Action::Numbers, &settings));
+        commands.spawn(button(Action::Language, &settings));
+        commands.spawn(button(Action::Theme, &settings));
+        commands.spawn(button(Action::SoundVolume, &settings));
+        commands.spawn(button(Action::MusicVolume, &settings));
+        commands.spawn(button(Action::Quit, &settings));
     });
 }
 
+#[derive(Component)]
+struct MenuTitle;
+
 #[derive(Component)]
 enum Action {
     Play,
+    Tutorial,
+    Mode,
+    Difficulty,
+    EvaluationLimit,
+    LiveValidation,
+    Numbers,
+    Language,
+    Theme,
+    SoundVolume,
+    MusicVolume,
     Quit,
 }
 
-fn button(action: Action) -> impl Bundle {
-    let text = match action {
-        Action::Play => "Play",
-        Action::Quit => "Quit",
-    };
-    
+impl Action {
+    fn label(&self, settings: &Settings) -> String {
+        let language = settings.language;
+        let format = |key: &str, argument: &str| language.format(language.get(key), &[argument]);
+        match self {
+            Action::Play => language.get("menu.play").to_string(),
+            Action::Tutorial => language.get("menu.tutorial").to_string(),
+            Action::Mode => format("menu.mode", match settings.mode {
+                Mode::Swap => language.get("mode.swap"),
+                Mode::TruthOnly => language.get("mode.truth_only"),
+                Mode::TokenBank => language.get("mode.token_bank"),
+                Mode::Reorder => language.get("mode.reorder"),
+            }),
+            Action::Difficulty => format("menu.difficulty", match settings.difficulty {
+                Difficulty::Normal => language.get("difficulty.normal"),
+                Difficulty::Hard => language.get("difficulty.hard"),
+            }),
+            Action::EvaluationLimit => match settings.evaluation_limit {
+                Some(limit) => format("menu.evaluation_limit", &limit.to_string()),
+                None => format("menu.evaluation_limit", language.get("menu.unlimited")),
+            },
+            Action::LiveValidation => if settings.live_validation {
+                language.get("menu.live_validation.on").to_string()
+            } else {
+                language.get("menu.live_validation.off").to_string()
+            },
+            Action::Numbers => if settings.spelled_numbers {
+                language.get("menu.numbers.words").to_string()
+            } else {
+                language.get("menu.numbers.digits").to_string()
+            },
+            Action::Language => format("menu.language", language.get("language.name")),
+            Action::Theme => format("menu.theme", language.get(settings.theme.get().name)),
+            Action::SoundVolume => format("menu.sound_volume", &((settings.sound_volume * 100.0).round() as u32).to_string()),
+            Action::MusicVolume => format("menu.music_volume", &((settings.music_volume * 100.0).round() as u32).to_string()),
+            Action::Quit => language.get("menu.quit").to_string(),
+        }
+    }
+}
+
+fn button(action: Action, settings: &Settings) -> impl Bundle {
+    let text = action.label(settings);
+
     (
-        Name::new(text),
+        Name::new(text.clone()),
         Button,
+        Focusable,
         action,
         Text::new(text),
-        TextColor(Color::BLACK),
+        TextColor(settings.theme.get().menu_text),
         TextFont {
             font_size: 40.0,
             ..default()
@@ -79,32 +144,104 @@ fn button(action: Action) -> impl Bundle {
 }
 
 fn button_interaction(
-    mut interaction_query: Query<(&Interaction, &Action, &mut TextColor), (Changed<Interaction>, With<Button>)>,
-    mut next_state: ResMut<NextState<GameState>>,
-    mut exit: EventWriter<AppExit>
+    mut commands: Commands,
+    mut interaction_query: Query<(Entity, &Interaction, &mut TextColor), (Changed<Interaction>, With<Action>)>,
+    settings: Res<Settings>,
 ) {
-    for (interaction, action, mut text_color) in interaction_query.iter_mut() {
+    for (entity, interaction, mut text_color) in interaction_query.iter_mut() {
         match *interaction {
             Interaction::Pressed => {
-                match action {
-                    Action::Play => {
-                        next_state.set(GameState::InGame);
-                    }
-                    Action::Quit => {
-                        exit.write(AppExit::Success);
-                    }
-                }
+                commands.run_system_cached_with(perform_action, entity);
             },
             Interaction::Hovered => {
                 text_color.0 = Color::srgb(0.5, 0.5, 0.5);
             }
             Interaction::None => {
-                text_color.0 = Color::BLACK;
+                text_color.0 = settings.theme.get().menu_text;
             }
         }
     }
 }
 
+fn activate_button(
+    mut commands: Commands,
+    mut activations: EventReader<Activate>,
+    actions: Query<(), With<Action>>,
+) {
+    for &Activate(entity) in activations.read() {
+        if actions.contains(entity) {
+            commands.run_system_cached_with(perform_action, entity);
+        }
+    }
+}
+
+fn perform_action(
+    button: In<Entity>,
+    mut commands: Commands,
+    actions: Query<&Action>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut exit: EventWriter<AppExit>,
+    mut settings: ResMut<Settings>,
+) {
+    match actions.get(*button).unwrap() {
+        Action::Play => {
+            next_state.set(GameState::InGame);
+        }
+        Action::Tutorial => {
+            commands.insert_resource(Tutorial::start(&mut settings));
+            next_state.set(GameState::InGame);
+        }
+        Action::Mode => {
+            settings.mode = settings.mode.next();
+        }
+        Action::Difficulty => {
+            settings.difficulty = settings.difficulty.next();
+        }
+        Action::EvaluationLimit => {
+            let index = EVALUATION_LIMITS.iter().position(|&limit| limit == settings.evaluation_limit).unwrap_or(0);
+            settings.evaluation_limit = EVALUATION_LIMITS[(index + 1) % EVALUATION_LIMITS.len()];
+        }
+        Action::LiveValidation => {
+            settings.live_validation = !settings.live_validation;
+        }
+        Action::Numbers => {
+            settings.spelled_numbers = !settings.spelled_numbers;
+        }
+        Action::Language => {
+            settings.language = settings.language.next();
+        }
+        Action::Theme => {
+            settings.theme = settings.theme.next();
+        }
+        Action::SoundVolume => {
+            settings.sound_volume = next_volume(settings.sound_volume);
+        }
+        Action::MusicVolume => {
+            settings.music_volume = next_volume(settings.music_volume);
+        }
+        Action::Quit => {
+            exit.write(AppExit::Success);
+        }
+    }
+}
+
+fn update_labels(
+    settings: Res<Settings>,
+    buttons: Query<(&Action, &mut Text, &mut TextColor), Without<MenuTitle>>,
+    title: Single<(&mut Text, &mut TextColor), With<MenuTitle>>,
+    mut background: Single<&mut BackgroundColor, With<MainMenu>>,
+) {
+    let theme = settings.theme.get();
+    for (action, mut text, mut text_color) in buttons {
+        text.0 = action.label(&settings);
+        text_color.0 = theme.menu_text;
+    }
+    let (mut title, mut title_color) = title.into_inner();
+    title.0 = settings.language.get("menu.title").to_string();
+    title_color.0 = theme.menu_text;
+    background.0 = theme.background;
+}
+
 fn despawn_menu(
     mut commands: Commands, 
     menu: Single<Entity, With<MainMenu>>
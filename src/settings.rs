@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+use crate::{locale::Language, theme::ThemeId};
+
+#[derive(Resource)]
+pub struct Settings {
+    pub difficulty: Difficulty,
+    pub mode: Mode,
+    /// How many times per level the `Evaluate` button can be used in hard mode.
+    pub evaluation_limit: Option<u32>,
+    /// Recompute consistency after every change and show it next to each sentence.
+    pub live_validation: bool,
+    /// Write numbers in sentences as words ("three") instead of digits.
+    pub spelled_numbers: bool,
+    pub language: Language,
+    pub theme: ThemeId,
+    /// Linear volumes from 0 to 1.
+    pub sound_volume: f32,
+    pub music_volume: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            difficulty: Difficulty::default(),
+            mode: Mode::default(),
+            evaluation_limit: None,
+            live_validation: false,
+            spelled_numbers: false,
+            language: Language::default(),
+            theme: ThemeId::default(),
+            sound_volume: 0.75,
+            music_volume: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    /// `Evaluate` can be pressed as often as needed.
+    #[default]
+    Normal,
+    /// `Evaluate` can only be pressed `evaluation_limit` times per level.
+    Hard,
+}
+
+impl Difficulty {
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Normal,
+        }
+    }
+}
+
+/// How the player is allowed to change the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// Tokens of the same kind can be swapped, and checkboxes marked.
+    #[default]
+    Swap,
+    /// Tokens stay where they are and only the checkboxes are marked, like a classic logic puzzle.
+    TruthOnly,
+    /// Sentences start out blank and are filled in from a bank of tokens, some of which fit nowhere.
+    TokenBank,
+    /// Tokens stay in their sentences and whole sentences are dragged into a different order.
+    Reorder,
+}
+
+impl Mode {
+    pub fn next(self) -> Self {
+        match self {
+            Mode::Swap => Mode::TruthOnly,
+            Mode::TruthOnly => Mode::TokenBank,
+            Mode::TokenBank => Mode::Reorder,
+            Mode::Reorder => Mode::Swap,
+        }
+    }
+}
+
+pub const EVALUATION_LIMITS: [Option<u32>; 4] = [None, Some(5), Some(3), Some(1)];
+
+pub const VOLUMES: [f32; 5] = [1.0, 0.75, 0.5, 0.25, 0.0];
+
+/// The volume that comes after `volume` when cycling through `VOLUMES`.
+pub fn next_volume(volume: f32) -> f32 {
+    let index = VOLUMES.iter().position(|&v| v == volume).unwrap_or(0);
+    VOLUMES[(index + 1) % VOLUMES.len()]
+}
@@ -0,0 +1,142 @@
+use bevy::{audio::Volume, prelude::*};
+
+use crate::settings::Settings;
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<PlaySound>()
+            .init_resource::<SoundQueue>()
+            .add_systems(Startup, (load_sounds, start_music).chain())
+            .add_systems(Update, play_sounds)
+            .add_systems(Update, update_music_volume.run_if(resource_changed::<Settings>));
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Sound {
+    Select,
+    Swap,
+    Reject,
+    Toggle,
+    Fail,
+    Solve,
+}
+
+#[derive(Event, Clone, Copy)]
+pub struct PlaySound {
+    pub sound: Sound,
+    /// Seconds to wait first, so a series of sounds can be told apart.
+    pub delay: f32,
+}
+
+impl PlaySound {
+    pub fn new(sound: Sound) -> Self {
+        Self { sound, delay: 0.0 }
+    }
+
+    pub fn after(self, delay: f32) -> Self {
+        Self { delay, ..self }
+    }
+}
+
+#[derive(Resource)]
+struct Sounds {
+    select: Handle<AudioSource>,
+    swap: Handle<AudioSource>,
+    reject: Handle<AudioSource>,
+    toggle: Handle<AudioSource>,
+    fail: Handle<AudioSource>,
+    solve: Handle<AudioSource>,
+}
+
+impl Sounds {
+    fn get(&self, sound: Sound) -> Handle<AudioSource> {
+        match sound {
+            Sound::Select => self.select.clone(),
+            Sound::Swap => self.swap.clone(),
+            Sound::Reject => self.reject.clone(),
+            Sound::Toggle => self.toggle.clone(),
+            Sound::Fail => self.fail.clone(),
+            Sound::Solve => self.solve.clone(),
+        }
+    }
+}
+
+/// Sounds waiting for their delay to run out.
+#[derive(Resource, Default)]
+struct SoundQueue(Vec<(Sound, Timer)>);
+
+#[derive(Component)]
+struct Music;
+
+fn load_sounds(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(Sounds {
+        select: asset_server.load("sounds/select.wav"),
+        swap: asset_server.load("sounds/swap.wav"),
+        reject: asset_server.load("sounds/reject.wav"),
+        toggle: asset_server.load("sounds/toggle.wav"),
+        fail: asset_server.load("sounds/fail.wav"),
+        solve: asset_server.load("sounds/solve.wav"),
+    });
+}
+
+fn start_music(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+) {
+    commands.spawn((
+        Name::new("Music"),
+        Music,
+        AudioPlayer::new(asset_server.load("sounds/music.wav")),
+        PlaybackSettings::LOOP.with_volume(Volume::Linear(settings.music_volume)),
+    ));
+}
+
+fn play_sounds(
+    mut commands: Commands,
+    mut events: EventReader<PlaySound>,
+    mut queue: ResMut<SoundQueue>,
+    sounds: Res<Sounds>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+) {
+    for &PlaySound { sound, delay } in events.read() {
+        queue.0.push((sound, Timer::from_seconds(delay, TimerMode::Once)));
+    }
+
+    let mut ready = Vec::new();
+    queue.0.retain_mut(|(sound, timer)| {
+        let finished = timer.tick(time.delta()).finished();
+        if finished {
+            ready.push(*sound);
+        }
+        !finished
+    });
+
+    if settings.sound_volume <= 0.0 {
+        return;
+    }
+    for sound in ready {
+        commands.spawn((
+            Name::new(format!("{sound:?} sound")),
+            AudioPlayer::new(sounds.get(sound)),
+            PlaybackSettings::DESPAWN.with_volume(Volume::Linear(settings.sound_volume)),
+        ));
+    }
+}
+
+fn update_music_volume(
+    settings: Res<Settings>,
+    music: Query<&mut AudioSink, With<Music>>,
+) {
+    for mut sink in music {
+        sink.set_volume(Volume::Linear(settings.music_volume));
+    }
+}
@@ -0,0 +1,132 @@
+use std::sync::LazyLock;
+
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+
+use crate::{locale::parse_strings, settings::Settings};
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, apply_font.run_if(resource_changed::<Settings>.or(any_match_filter::<Added<TextFont>>)));
+    }
+}
+
+/// The themes that can be picked in the menu. Each one has a `key = value` file in `assets/themes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThemeId {
+    #[default]
+    Default,
+    ColorBlind,
+    HighContrast,
+}
+
+static DEFAULT: LazyLock<Theme> = LazyLock::new(|| Theme::load("default.txt", include_str!("../assets/themes/default.txt")));
+static COLOR_BLIND: LazyLock<Theme> = LazyLock::new(|| Theme::load("color_blind.txt", include_str!("../assets/themes/color_blind.txt")));
+static HIGH_CONTRAST: LazyLock<Theme> = LazyLock::new(|| Theme::load("high_contrast.txt", include_str!("../assets/themes/high_contrast.txt")));
+
+impl ThemeId {
+    pub fn next(self) -> Self {
+        match self {
+            ThemeId::Default => ThemeId::ColorBlind,
+            ThemeId::ColorBlind => ThemeId::HighContrast,
+            ThemeId::HighContrast => ThemeId::Default,
+        }
+    }
+
+    pub fn get(self) -> &'static Theme {
+        match self {
+            ThemeId::Default => &DEFAULT,
+            ThemeId::ColorBlind => &COLOR_BLIND,
+            ThemeId::HighContrast => &HIGH_CONTRAST,
+        }
+    }
+}
+
+pub struct Theme {
+    /// Translation key of the theme's name.
+    pub name: &'static str,
+    pub background: Color,
+    /// Text drawn straight on the background, like the menu.
+    pub menu_text: Color,
+    /// Text written in the notebook.
+    pub ink: Color,
+    pub tooltip: Color,
+    pub focus: Color,
+    pub id_token: Color,
+    pub bool_token: Color,
+    pub number_token: Color,
+    pub consistent: Color,
+    pub inconsistent: Color,
+    pub determined: Color,
+    pub highlight_primary: Color,
+    pub highlight_secondary: Color,
+    pub notebook: &'static str,
+    pub checkbox_true: &'static str,
+    pub checkbox_false: &'static str,
+    pub checkbox_unknown: &'static str,
+    /// Replaces Bevy's built-in font everywhere when set.
+    pub font: Option<&'static str>,
+}
+
+impl Theme {
+    /// Reads the theme from `assets/themes` the first time it is used, so it can be edited without rebuilding the game.
+    /// The copy built into the game stands in where there is no assets folder to read, like on the web.
+    fn load(file: &str, built_in: &'static str) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = FileAssetReader::get_base_path().join("assets/themes").join(file);
+            match std::fs::read_to_string(&path) {
+                Ok(source) => return Self::parse(source.leak()),
+                Err(error) => warn!("Using the built-in theme, could not read {}: {error}", path.display()),
+            }
+        }
+        Self::parse(built_in)
+    }
+
+    fn parse(source: &'static str) -> Self {
+        let strings = parse_strings(source);
+        let string = |key: &str| *strings.get(key).unwrap_or_else(|| panic!("Theme is missing {key}"));
+        let color = |key: &str| -> Color {
+            Srgba::hex(string(key)).unwrap_or_else(|error| panic!("Invalid color for {key}: {error}")).into()
+        };
+
+        Self {
+            name: string("name"),
+            background: color("background"),
+            menu_text: color("menu_text"),
+            ink: color("ink"),
+            tooltip: color("tooltip"),
+            focus: color("focus"),
+            id_token: color("token.id"),
+            bool_token: color("token.bool"),
+            number_token: color("token.number"),
+            consistent: color("consistent"),
+            inconsistent: color("inconsistent"),
+            determined: color("determined"),
+            highlight_primary: color("highlight.primary"),
+            highlight_secondary: color("highlight.secondary"),
+            notebook: string("notebook"),
+            checkbox_true: string("checkbox.true"),
+            checkbox_false: string("checkbox.false"),
+            checkbox_unknown: string("checkbox.unknown"),
+            font: strings.get("font").copied(),
+        }
+    }
+}
+
+fn apply_font(
+    settings: Res<Settings>,
+    texts: Query<&mut TextFont>,
+    asset_server: Res<AssetServer>,
+) {
+    let font = settings.theme.get().font.map(|path| asset_server.load(path)).unwrap_or_default();
+    for mut text_font in texts {
+        if text_font.font != font {
+            text_font.font = font.clone();
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use bevy::{ecs::query::QueryData, prelude::*};
+
+pub struct TweenPlugin;
+
+impl Plugin for TweenPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, animate);
+    }
+}
+
+/// Something about a UI node that a tween moves from one value to another.
+#[derive(Clone, Copy)]
+pub enum Property {
+    /// `left` and `top` in logical pixels. For relatively positioned nodes this is an offset from where layout puts them.
+    Position(Vec2, Vec2),
+    /// Damped sideways wiggle of `Position` around the origin, ignoring the easing curve.
+    Shake { amplitude: f32, frequency: f32 },
+    Scale(Vec2, Vec2),
+    /// Clockwise angle in radians.
+    Rotation(f32, f32),
+    BackgroundColor(Color, Color),
+    /// Alpha of the node's own text, image, background and border; children keep theirs.
+    /// A transparent background or border stays transparent.
+    Opacity(f32, f32),
+}
+
+#[derive(Clone, Copy)]
+pub struct Tween {
+    property: Property,
+    duration: f32,
+    ease: EaseFunction,
+}
+
+impl Tween {
+    pub fn new(property: Property, duration: f32) -> Self {
+        Self {
+            property,
+            duration,
+            ease: EaseFunction::Linear,
+        }
+    }
+
+    pub fn ease(self, ease: EaseFunction) -> Self {
+        Self { ease, ..self }
+    }
+
+    /// Sets the property to where it is `elapsed` seconds into the tween.
+    fn apply(&self, elapsed: f32, target: &mut AnimatedItem) {
+        let progress = if self.duration > 0.0 { (elapsed / self.duration).clamp(0.0, 1.0) } else { 1.0 };
+        let t = self.ease.sample_clamped(progress);
+        match self.property {
+            Property::Position(from, to) => set_position(target, from.lerp(to, t)),
+            Property::Shake { amplitude, frequency } => {
+                let offset = amplitude * (1.0 - progress) * (elapsed * frequency).sin();
+                set_position(target, Vec2::new(offset, 0.0));
+            }
+            Property::Scale(from, to) => {
+                target.transform.scale = from.lerp(to, t).extend(1.0);
+            }
+            Property::Rotation(from, to) => {
+                target.transform.rotation = Quat::from_rotation_z(-from.lerp(to, t));
+            }
+            Property::BackgroundColor(from, to) => {
+                target.background.0 = from.to_linear().mix(&to.to_linear(), t).into();
+            }
+            Property::Opacity(from, to) => {
+                let alpha = from.lerp(to, t);
+                for color in [&mut target.background.0, &mut target.border.0] {
+                    if *color != Color::NONE {
+                        color.set_alpha(alpha);
+                    }
+                }
+                if let Some(text_color) = target.text_color.as_mut() {
+                    text_color.0.set_alpha(alpha);
+                }
+                if let Some(image) = target.image.as_mut() {
+                    image.color.set_alpha(alpha);
+                }
+            }
+        }
+    }
+}
+
+fn set_position(target: &mut AnimatedItem, position: Vec2) {
+    target.node.left = Val::Px(position.x);
+    target.node.top = Val::Px(position.y);
+}
+
+/// Plays tweens on a UI node. Tweens added `with` each other run together, and each `then` waits for them to finish.
+/// Inserting a new animation replaces the one already playing.
+#[derive(Component, Clone)]
+pub struct Animation {
+    steps: Vec<Vec<Tween>>,
+    step: usize,
+    elapsed: f32,
+    despawn: bool,
+}
+
+impl Animation {
+    pub fn new(tween: Tween) -> Self {
+        Self {
+            steps: vec![vec![tween]],
+            step: 0,
+            elapsed: 0.0,
+            despawn: false,
+        }
+    }
+
+    pub fn with(mut self, tween: Tween) -> Self {
+        self.steps.last_mut().unwrap().push(tween);
+        self
+    }
+
+    pub fn then(mut self, tween: Tween) -> Self {
+        self.steps.push(vec![tween]);
+        self
+    }
+
+    /// Holds the first step at its starting values for `delay` seconds.
+    pub fn after(self, delay: f32) -> Self {
+        Self { elapsed: -delay, ..self }
+    }
+
+    /// Despawns the node instead of leaving it where the last step ended.
+    pub fn despawn_when_finished(self) -> Self {
+        Self { despawn: true, ..self }
+    }
+
+    fn step_duration(&self) -> f32 {
+        self.steps[self.step].iter().map(|tween| tween.duration).fold(0.0, f32::max)
+    }
+}
+
+/// Triggered on an entity when its `Animation` has played to the end.
+#[derive(Event)]
+pub struct AnimationFinished;
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+struct Animated {
+    node: &'static mut Node,
+    transform: &'static mut Transform,
+    background: &'static mut BackgroundColor,
+    border: &'static mut BorderColor,
+    text_color: Option<&'static mut TextColor>,
+    image: Option<&'static mut ImageNode>,
+}
+
+fn animate(
+    mut commands: Commands,
+    animations: Query<(Entity, &mut Animation, Animated)>,
+    time: Res<Time>,
+) {
+    for (entity, mut animation, mut target) in animations {
+        animation.elapsed += time.delta_secs();
+
+        // A long frame can finish several steps at once; each one still gets to land on its final values.
+        while animation.step < animation.steps.len() && animation.elapsed >= animation.step_duration() {
+            animation.elapsed -= animation.step_duration();
+            for tween in animation.steps[animation.step].iter() {
+                tween.apply(tween.duration, &mut target);
+            }
+            animation.step += 1;
+        }
+
+        if animation.step == animation.steps.len() {
+            if animation.despawn {
+                commands.entity(entity).despawn();
+            } else {
+                commands.entity(entity).remove::<Animation>();
+                commands.trigger_targets(AnimationFinished, entity);
+            }
+            continue;
+        }
+
+        for tween in animation.steps[animation.step].iter() {
+            tween.apply(animation.elapsed, &mut target);
+        }
+    }
+}